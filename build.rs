@@ -30,14 +30,26 @@ fn main() {
     //#define VMA_HEAVY_ASSERT(expr) assert(expr)
     //#define VMA_USE_STL_CONTAINERS 1
     //#define VMA_DEDICATED_ALLOCATION 0
-    //#define VMA_DEBUG_MARGIN 16
-    //#define VMA_DEBUG_DETECT_CORRUPTION 1
-    //#define VMA_DEBUG_INITIALIZE_ALLOCATIONS 1
     //#define VMA_DEBUG_MIN_BUFFER_IMAGE_GRANULARITY 256
 
     #[cfg(feature = "recording")]
     build.define("VMA_RECORDING_ENABLED", "1");
 
+    // Reserve a margin after every allocation and fill it with a magic number,
+    // so that `check_corruption` can detect out-of-bounds writes. Detected
+    // corruption is returned as an error instead of aborting in VMA_ASSERT.
+    #[cfg(feature = "corruption_detection")]
+    {
+        build.define("VMA_DEBUG_MARGIN", "16");
+        build.define("VMA_DEBUG_DETECT_CORRUPTION", "1");
+        build.define("VMA_RUST_NONFATAL_CORRUPTION_ASSERT", "1");
+    }
+
+    // Fill new allocations with 0xDCDCDCDC and freed ones with 0xEFEFEFEF,
+    // to make reads of uninitialized or freed memory stand out.
+    #[cfg(feature = "debug_initialize")]
+    build.define("VMA_DEBUG_INITIALIZE_ALLOCATIONS", "1");
+
     // Add the files we build
     let source_files = ["wrapper/vma_lib.cpp"];
 
@@ -3,8 +3,7 @@
 use erupt;
 #[cfg(feature = "failure")]
 use failure::{Backtrace, Context, Fail};
-#[cfg(not(feature = "failure"))]
-use std::error::Error as StdError;
+#[cfg(feature = "failure")]
 use std::fmt;
 use std::path::{Path, PathBuf};
 use std::result;
@@ -13,12 +12,19 @@ use std::result;
 pub type Result<T> = result::Result<T, Error>;
 
 /// An error that can occur
+#[cfg(not(feature = "failure"))]
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct Error {
+    #[from]
+    kind: ErrorKind,
+}
+
+/// An error that can occur
+#[cfg(feature = "failure")]
 #[derive(Debug)]
 pub struct Error {
-    #[cfg(feature = "failure")]
     ctx: Context<ErrorKind>,
-    #[cfg(not(feature = "failure"))]
-    kind: ErrorKind,
 }
 
 impl Error {
@@ -53,6 +59,18 @@ impl Error {
         Error::from(ErrorKind::Config(msg.as_ref().to_string()))
     }
 
+    pub fn internal<T: AsRef<str>>(msg: T) -> Error {
+        Error::from(ErrorKind::Internal(msg.as_ref().to_string()))
+    }
+
+    pub fn unsupported<T: AsRef<str>>(msg: T) -> Error {
+        Error::from(ErrorKind::Unsupported(msg.as_ref().to_string()))
+    }
+
+    pub fn destroyed() -> Error {
+        Error::from(ErrorKind::Destroyed)
+    }
+
     #[cfg(feature = "failure")]
     pub fn number<E: Fail>(err: E) -> Error {
         Error::from(err.context(ErrorKind::Number))
@@ -70,61 +88,71 @@ impl Fail for Error {
     }
 }
 
-#[cfg(not(feature = "failure"))]
-impl StdError for Error {
-    fn source(&self) -> Option<&(dyn StdError + 'static)> {
-        match self.kind {
-            ErrorKind::Vulkan(ref err) => Some(err),
-            _ => None,
-        }
-    }
-}
-
+// With `failure` enabled, `Error` implements `Fail` instead of `std::error::Error`,
+// because `failure` provides a blanket `Fail` impl for every `std::error::Error`.
+#[cfg(feature = "failure")]
 impl fmt::Display for Error {
-    #[cfg(feature = "failure")]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.ctx.fmt(f)
     }
-
-    #[cfg(not(feature = "failure"))]
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        self.kind.fmt(f)
-    }
 }
 
 /// The specific kind of error that can occur.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
 pub enum ErrorKind {
     /// An error that occurred while interacting with Vulkan
-    Vulkan(erupt::vk::Result),
+    #[error("vulkan error: {0:?}")]
+    Vulkan(#[source] erupt::vk::Result),
 
     /// An error that occurred while accessing or allocating memory
+    #[error("memory error: {0}")]
     Memory(String),
 
     /// An error that occurred while parsing a data source
+    #[error("parse error: {0}")]
     Parse(String),
 
     /// An error that occurred while working with a file path.
+    #[error("{}", .0.display())]
     Path(PathBuf),
 
     /// Generally, these errors correspond to bugs in this library.
+    #[error(
+        "BUG: {0}\nPlease report this bug with a backtrace at https://github.com/gwihlidal/vk-mem-rs"
+    )]
     Bug(String),
 
     /// An error occurred while reading/writing a configuration
+    #[error("config error: {0}")]
     Config(String),
 
     /// An unexpected I/O error occurred.
+    #[error("I/O error")]
     Io,
 
     /// An error occurred while parsing a number in a free-form query.
+    #[error("error parsing number")]
     Number,
 
+    /// An unexpected error occurred inside of the wrapper.
+    #[error("internal error: {0}")]
+    Internal(String),
+
+    /// An operation was requested that the library doesn't support for the given arguments.
+    #[error("unsupported operation: {0}")]
+    Unsupported(String),
+
+    /// The allocator was used after `Allocator::destroy` was called on it.
+    #[error("allocator has been destroyed")]
+    Destroyed,
+
     /// Hints that destructuring should not be exhaustive.
     ///
     /// This enum may grow additional variants, so this makes sure clients
     /// don't count on exhaustive matching. (Otherwise, adding a new variant
     /// could break existing code.)
     #[doc(hidden)]
+    #[error("invalid error")]
     __Nonexhaustive,
 }
 
@@ -135,33 +163,6 @@ impl ErrorKind {
     }
 }
 
-impl fmt::Display for ErrorKind {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
-            ErrorKind::Vulkan(ref msg) => write!(f, "vulkan error: {}", msg),
-            ErrorKind::Memory(ref msg) => write!(f, "memory error: {}", msg),
-            ErrorKind::Parse(ref msg) => write!(f, "parse error: {}", msg),
-            ErrorKind::Path(ref path) => write!(f, "{}", path.display()),
-            ErrorKind::Bug(ref msg) => {
-                let report = "Please report this bug with a backtrace at \
-                              https://github.com/gwihlidal/vk-mem-rs";
-                write!(f, "BUG: {}\n{}", msg, report)
-            }
-            ErrorKind::Config(ref msg) => write!(f, "config error: {}", msg),
-            ErrorKind::Io => write!(f, "I/O error"),
-            ErrorKind::Number => write!(f, "error parsing number"),
-            ErrorKind::__Nonexhaustive => panic!("invalid error"),
-        }
-    }
-}
-
-#[cfg(not(feature = "failure"))]
-impl From<ErrorKind> for Error {
-    fn from(kind: ErrorKind) -> Error {
-        Error { kind }
-    }
-}
-
 #[cfg(feature = "failure")]
 impl From<ErrorKind> for Error {
     fn from(kind: ErrorKind) -> Error {
@@ -0,0 +1,128 @@
+//! Transient per-frame allocator, built on top of a linear custom pool.
+
+use crate::error::Result;
+use crate::{
+    Allocation, AllocationCreateFlags, AllocationCreateInfo, AllocationInfo, Allocator,
+    AllocatorPool, AllocatorPoolCreateFlags, AllocatorPoolCreateInfo, Shared,
+};
+
+/// Bump allocator for memory and buffers that live for a single frame.
+///
+/// Everything is allocated from a custom pool created with
+/// `AllocatorPoolCreateFlags::LINEAR_ALGORITHM`. The `FrameAllocator` remembers what was
+/// allocated and `FrameAllocator::begin_frame` frees all of it in one go, so the linear
+/// algorithm can keep placing new allocations one after another without ever searching
+/// for free space. Unlike `RingAllocator`, the pool grows by another block when the
+/// current ones are full.
+///
+/// Allocations and buffers returned by this allocator must not be used, and must not be
+/// freed or destroyed manually, after the next call to `FrameAllocator::begin_frame`.
+/// If the GPU still reads them, wait for the corresponding frame to finish before beginning
+/// a new one.
+///
+/// The pool and everything allocated from it are freed when the `FrameAllocator` is dropped.
+pub struct FrameAllocator<'a, S = Shared> {
+    allocator: &'a Allocator<S>,
+    pool: AllocatorPool,
+    /// Buffers created in the current frame
+    buffers: Vec<erupt::vk::Buffer>,
+    /// Allocations made in the current frame, including the ones of `buffers`
+    allocations: Vec<Allocation>,
+}
+
+impl<'a, S> FrameAllocator<'a, S> {
+    /// Creates a frame allocator in memory type `memory_type_index`, allocating memory in
+    /// blocks of `block_size` bytes, or of the default size if `block_size` is 0.
+    pub fn new(
+        allocator: &'a Allocator<S>,
+        memory_type_index: u32,
+        block_size: usize,
+    ) -> Result<Self> {
+        let pool = allocator.create_pool(&AllocatorPoolCreateInfo {
+            memory_type_index,
+            flags: AllocatorPoolCreateFlags::LINEAR_ALGORITHM,
+            block_size,
+            ..Default::default()
+        })?;
+        Ok(FrameAllocator {
+            allocator,
+            pool,
+            buffers: Vec::new(),
+            allocations: Vec::new(),
+        })
+    }
+
+    /// Starts a new frame: informs the allocator about it with `Allocator::set_current_frame_index`
+    /// and frees everything allocated during the previous frame.
+    pub fn begin_frame(&mut self, frame_index: u32) {
+        self.allocator.set_current_frame_index(frame_index);
+        self.free_frame();
+    }
+
+    /// Allocates memory for the current frame.
+    ///
+    /// `memory_requirements.memory_type_bits` must include the memory type of the pool.
+    pub fn allocate(
+        &mut self,
+        memory_requirements: &erupt::vk::MemoryRequirements,
+        flags: AllocationCreateFlags,
+    ) -> Result<(Allocation, AllocationInfo)> {
+        let (allocation, allocation_info) = self
+            .allocator
+            .allocate_memory(memory_requirements, &self.allocation_create_info(flags))?;
+        self.allocations.push(allocation);
+        Ok((allocation, allocation_info))
+    }
+
+    /// Creates a buffer bound to memory allocated for the current frame.
+    pub fn create_buffer(
+        &mut self,
+        buffer_info: &erupt::vk::BufferCreateInfo,
+        flags: AllocationCreateFlags,
+    ) -> Result<(erupt::vk::Buffer, Allocation, AllocationInfo)> {
+        let (buffer, allocation, allocation_info) = self
+            .allocator
+            .create_buffer(buffer_info, &self.allocation_create_info(flags))?;
+        self.buffers.push(buffer);
+        self.allocations.push(allocation);
+        Ok((buffer, allocation, allocation_info))
+    }
+
+    /// Returns the number of allocations made in the current frame.
+    pub fn allocation_count(&self) -> usize {
+        self.allocations.len()
+    }
+
+    /// Returns the custom pool backing this allocator.
+    pub fn pool(&self) -> &AllocatorPool {
+        &self.pool
+    }
+
+    fn allocation_create_info(&self, flags: AllocationCreateFlags) -> AllocationCreateInfo {
+        AllocationCreateInfo {
+            flags,
+            pool: Some(self.pool.clone()),
+            ..Default::default()
+        }
+    }
+
+    /// Destroys the buffers of the current frame, then frees all of its memory with a single
+    /// `Allocator::free_memory_pages` call.
+    fn free_frame(&mut self) {
+        for buffer in self.buffers.drain(..) {
+            // Destroying a buffer with a null allocation doesn't free anything.
+            self.allocator.destroy_buffer(buffer, &Allocation::null());
+        }
+        if !self.allocations.is_empty() {
+            self.allocator.free_memory_pages(&self.allocations);
+            self.allocations.clear();
+        }
+    }
+}
+
+impl<S> Drop for FrameAllocator<'_, S> {
+    fn drop(&mut self) {
+        self.free_frame();
+        self.allocator.destroy_pool(&self.pool);
+    }
+}
@@ -0,0 +1,157 @@
+//! `std::io` adapters for reading and writing memory of host visible allocations.
+
+use crate::error::Result;
+use crate::{Allocation, Allocator, Shared};
+use std::io;
+
+/// Sequential writer into the memory of an allocation, created by `Allocator::writer`.
+///
+/// The allocation is mapped for as long as the writer is alive. Each write copies bytes at the
+/// current position and advances it; writing when the position has reached the end of the
+/// allocation fails with `std::io::ErrorKind::WriteZero`.
+///
+/// `Write::flush` calls `Allocator::flush_allocation` for the bytes written since the previous
+/// flush, which is a no-op for `HOST_COHERENT` memory. Bytes that weren't flushed explicitly are
+/// flushed when the writer is dropped, right before the allocation is unmapped.
+pub struct AllocationWriter<'a, S = Shared> {
+    allocator: &'a Allocator<S>,
+    allocation: Allocation,
+    data: *mut u8,
+    size: usize,
+    position: usize,
+    /// Start of the range written since the last flush
+    flushed: usize,
+}
+
+impl<'a, S> AllocationWriter<'a, S> {
+    pub(crate) fn new(allocator: &'a Allocator<S>, allocation: &Allocation) -> Result<Self> {
+        let size = allocator.get_allocation_info(allocation)?.get_size() as usize;
+        let data = allocator.map_memory(allocation)?;
+        Ok(AllocationWriter {
+            allocator,
+            allocation: *allocation,
+            data,
+            size,
+            position: 0,
+            flushed: 0,
+        })
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    fn flush_written(&mut self) {
+        if self.flushed < self.position {
+            self.allocator.flush_allocation(
+                &self.allocation,
+                self.flushed as erupt::vk::DeviceSize,
+                (self.position - self.flushed) as erupt::vk::DeviceSize,
+            );
+            self.flushed = self.position;
+        }
+    }
+}
+
+impl<S> io::Write for AllocationWriter<'_, S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let len = buf.len().min(self.size - self.position);
+        if len == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::WriteZero,
+                "end of allocation reached",
+            ));
+        }
+        unsafe {
+            std::ptr::copy_nonoverlapping(buf.as_ptr(), self.data.add(self.position), len);
+        }
+        self.position += len;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flush_written();
+        Ok(())
+    }
+}
+
+impl<S> Drop for AllocationWriter<'_, S> {
+    fn drop(&mut self) {
+        self.flush_written();
+        self.allocator.unmap_memory(&self.allocation);
+    }
+}
+
+/// Sequential reader of the memory of an allocation, created by `Allocator::reader`.
+///
+/// The whole allocation is invalidated once when the reader is created, which is a no-op for
+/// `HOST_COHERENT` memory, and stays mapped for as long as the reader is alive. Reading returns
+/// `Ok(0)` once the end of the allocation is reached.
+pub struct AllocationReader<'a, S = Shared> {
+    allocator: &'a Allocator<S>,
+    allocation: Allocation,
+    data: *const u8,
+    size: usize,
+    position: usize,
+}
+
+impl<'a, S> AllocationReader<'a, S> {
+    pub(crate) fn new(allocator: &'a Allocator<S>, allocation: &Allocation) -> Result<Self> {
+        let size = allocator.get_allocation_info(allocation)?.get_size() as usize;
+        let data = allocator.map_memory(allocation)?;
+        allocator.invalidate_allocation(allocation, 0, erupt::vk::WHOLE_SIZE);
+        Ok(AllocationReader {
+            allocator,
+            allocation: *allocation,
+            data,
+            size,
+            position: 0,
+        })
+    }
+
+    /// Returns the number of bytes read so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl<S> io::Read for AllocationReader<'_, S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.size - self.position);
+        unsafe {
+            std::ptr::copy_nonoverlapping(self.data.add(self.position), buf.as_mut_ptr(), len);
+        }
+        self.position += len;
+        Ok(len)
+    }
+}
+
+impl<S> Drop for AllocationReader<'_, S> {
+    fn drop(&mut self) {
+        self.allocator.unmap_memory(&self.allocation);
+    }
+}
+
+impl<S> Allocator<S> {
+    /// Maps the allocation and returns a `std::io::Write` implementation writing into its memory
+    /// from the beginning.
+    ///
+    /// The allocation must be in `erupt::vk::MemoryPropertyFlags::HOST_VISIBLE` memory.
+    pub fn writer(&self, allocation: &Allocation) -> Result<AllocationWriter<S>> {
+        AllocationWriter::new(self, allocation)
+    }
+
+    /// Maps and invalidates the allocation and returns a `std::io::Read` implementation reading
+    /// its memory from the beginning.
+    ///
+    /// The allocation must be in `erupt::vk::MemoryPropertyFlags::HOST_VISIBLE` memory. Memory
+    /// written by the GPU must be made available to the host, e.g. with a pipeline barrier and
+    /// a fence wait, before the reader is created.
+    pub fn reader(&self, allocation: &Allocation) -> Result<AllocationReader<S>> {
+        AllocationReader::new(self, allocation)
+    }
+}
@@ -10,32 +10,150 @@ extern crate failure;
 
 pub mod error;
 pub mod ffi;
+pub mod frame_allocator;
+pub mod io;
+#[cfg(feature = "bytemuck")]
+pub mod mapped;
+pub mod pool_set;
+pub mod ring_allocator;
+pub mod staging;
+#[cfg(feature = "stats_report")]
+pub mod stats_report;
+pub mod virtual_block;
 pub use crate::error::{Error, ErrorKind, Result};
+pub use crate::frame_allocator::FrameAllocator;
+pub use crate::io::{AllocationReader, AllocationWriter};
+#[cfg(feature = "bytemuck")]
+pub use crate::mapped::MappedRef;
+pub use crate::pool_set::PoolSet;
+pub use crate::ring_allocator::RingAllocator;
+pub use crate::staging::StagingUpload;
+#[cfg(feature = "stats_report")]
+pub use crate::stats_report::{
+    BudgetReport, DetailedStatsReport, HeapStatsReport, MemoryTypeStatsReport, StatsReport,
+};
+pub use crate::virtual_block::{
+    VirtualAllocation, VirtualAllocationCreateFlags, VirtualAllocationCreateInfo,
+    VirtualAllocationInfo, VirtualBlock, VirtualBlockCreateFlags, VirtualBlockCreateInfo,
+};
 use erupt::ObjectHandle;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
 use std::mem;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// Main allocator object
-pub struct Allocator {
+///
+/// Cloning an `Allocator` is cheap: all clones share the same internal `VmaAllocator`,
+/// which is destroyed when the last of them is dropped.
+///
+/// The type parameter tells whether the internal allocator synchronizes itself. It is `Shared`
+/// by default, making the allocator `Send + Sync`; see `LocalAllocator` for the other case.
+pub struct Allocator<S = Shared> {
+    inner: Arc<AllocatorInner>,
+    _sync: PhantomData<S>,
+}
+
+/// Marker for an `Allocator` that is internally synchronized and can be used from multiple
+/// threads simultaneously.
+#[derive(Debug)]
+pub enum Shared {}
+
+/// Marker for an `Allocator` created with `AllocatorCreateFlags::EXTERNALLY_SYNCHRONIZED`,
+/// see `LocalAllocator`.
+#[derive(Debug)]
+pub struct Local {
+    _not_sync: PhantomData<Cell<()>>,
+}
+
+/// Allocator created with `AllocatorCreateFlags::EXTERNALLY_SYNCHRONIZED`.
+///
+/// With this flag the library doesn't lock its internal mutexes, so calling into the same
+/// allocator from two threads at once is a data race. To rule that out at compile time, a
+/// `LocalAllocator` is `Send` but not `Sync`, and it can't be cloned: it may be moved to another
+/// thread, but it can only ever be used from one thread at a time. Wrap it in a `Mutex` to share it.
+pub type LocalAllocator = Allocator<Local>;
+
+impl Clone for Allocator {
+    fn clone(&self) -> Self {
+        Allocator {
+            inner: Arc::clone(&self.inner),
+            _sync: PhantomData,
+        }
+    }
+}
+
+/// State of an `Allocator` shared by all of its clones
+struct AllocatorInner {
     /// Pointer to internal VmaAllocator instance
     internal: ffi::VmaAllocator,
     /// Vulkan device handle
-    #[allow(dead_code)]
     device: Arc<erupt::DeviceLoader>,
     /// Vulkan instance handle
     #[allow(dead_code)]
     instance: Arc<erupt::InstanceLoader>,
+    /// Custom CPU memory allocation callbacks, kept alive for the lifetime of the allocator
+    allocation_callbacks: Option<Box<erupt::vk::AllocationCallbacks>>,
+    /// Device memory callbacks referenced by the internal allocator through `pUserData`
+    device_memory_callbacks: Option<Arc<DeviceMemoryCallbacks>>,
+    /// Create infos of pools created through `Allocator::create_pool` and not yet destroyed
+    pools: Mutex<HashMap<AllocatorPool, AllocatorPoolCreateInfo>>,
+    /// Properties of the physical device, fetched once when the allocator is created
+    physical_device_properties: erupt::vk::PhysicalDeviceProperties,
+    /// Memory properties of the physical device, fetched once when the allocator is created
+    memory_properties: erupt::vk::PhysicalDeviceMemoryProperties,
+    /// Flags the allocator was created with
+    create_flags: AllocatorCreateFlags,
+    /// Vulkan API version the allocator was created for
+    vulkan_api_version: u32,
+    /// Live allocations made in custom pools, with the pool each one belongs to
+    #[cfg(feature = "pool_tracking")]
+    pool_allocations: Mutex<HashMap<Allocation, AllocatorPool>>,
+    /// Whether `internal` was created by this crate and must be destroyed with it
+    owned: bool,
 }
 
-// Allocator is internally thread safe unless AllocatorCreateFlags::EXTERNALLY_SYNCHRONIZED is used (then you need to add synchronization!)
-unsafe impl Send for Allocator {}
-unsafe impl Sync for Allocator {}
+// The internal allocator is thread safe unless AllocatorCreateFlags::EXTERNALLY_SYNCHRONIZED is used,
+// in which case `Allocator` is `!Sync` through its `Local` marker.
+unsafe impl Send for AllocatorInner {}
+unsafe impl Sync for AllocatorInner {}
+
+/// Prints the internal `VmaAllocator` pointer and the Vulkan handles it was created with.
+///
+/// The alternate form, `{:#?}`, also calls `Allocator::calculate_statistics` and prints a short
+/// summary of the memory allocated so far. It is not done by default as it walks over all memory
+/// blocks of the allocator.
+impl<S> std::fmt::Debug for Allocator<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut s = f.debug_struct("Allocator");
+        s.field("internal", &self.inner.internal);
+        let info = match self.get_allocator_info() {
+            Ok(info) => info,
+            Err(_) => return s.finish(),
+        };
+        s.field("physical_device", &info.physical_device)
+            .field("device", &info.device);
+        if f.alternate() {
+            if let Ok(stats) = self.calculate_statistics() {
+                s.field("block_count", &stats.total.block_count)
+                    .field("block_bytes", &stats.total.block_bytes)
+                    .field("allocation_count", &stats.total.allocation_count)
+                    .field("allocation_bytes", &stats.total.allocation_bytes);
+            }
+        }
+        s.finish()
+    }
+}
 
 /// Represents custom memory pool
 ///
 /// Fill structure `AllocatorPoolCreateInfo` and call `Allocator::create_pool` to create it.
 /// Call `Allocator::destroy_pool` to destroy it.
-#[derive(Debug, Clone)]
+///
+/// Two `AllocatorPool`s compare equal, and hash identically, if and only if they refer to the
+/// same VMA pool handle. All default (null) pools are equal to each other.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct AllocatorPool {
     /// Pointer to internal VmaPool instance
     internal: ffi::VmaPool,
@@ -50,6 +168,24 @@ impl Default for AllocatorPool {
     }
 }
 
+impl AllocatorPool {
+    /// Returns the internal `VmaPool` handle.
+    pub fn as_raw(&self) -> ffi::VmaPool {
+        self.internal
+    }
+
+    /// Wraps an existing `VmaPool` handle.
+    ///
+    /// # Safety
+    ///
+    /// The handle must be null or a valid pool created by the same `VmaAllocator` it is used with.
+    /// No ownership is taken: the caller guarantees the pool outlives any usage of the
+    /// returned value and remains responsible for destroying it exactly once.
+    pub unsafe fn from_raw(internal: ffi::VmaPool) -> AllocatorPool {
+        AllocatorPool { internal }
+    }
+}
+
 /// Represents single memory allocation.
 ///
 /// It may be either dedicated block of `erupt::vk::DeviceMemory` or a specific region of a
@@ -66,7 +202,12 @@ impl Default for AllocatorPool {
 /// use `Allocator::get_allocation_info`.
 ///
 /// Some kinds allocations can be in lost state.
-#[derive(Debug, Copy, Clone)]
+///
+/// Two `Allocation`s compare equal, and hash identically, if and only if they refer to the
+/// same VMA allocation handle. This makes it possible to use them as keys to attach custom
+/// per-allocation metadata.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(transparent)]
 pub struct Allocation {
     /// Pointer to internal VmaAllocation instance
     internal: ffi::VmaAllocation,
@@ -78,6 +219,27 @@ impl Allocation {
             internal: std::ptr::null_mut(),
         }
     }
+
+    /// Returns true if this is a null allocation, e.g. the `Default` one.
+    pub fn is_null(&self) -> bool {
+        self.internal.is_null()
+    }
+
+    /// Returns the internal `VmaAllocation` handle.
+    pub fn as_raw(&self) -> ffi::VmaAllocation {
+        self.internal
+    }
+
+    /// Wraps an existing `VmaAllocation` handle.
+    ///
+    /// # Safety
+    ///
+    /// The handle must be null or a valid allocation made by the same `VmaAllocator` it is used with.
+    /// No ownership is taken: the caller guarantees the allocation outlives any usage of the
+    /// returned value and remains responsible for freeing it exactly once.
+    pub unsafe fn from_raw(internal: ffi::VmaAllocation) -> Allocation {
+        Allocation { internal }
+    }
 }
 
 impl Default for Allocation {
@@ -90,7 +252,7 @@ unsafe impl Send for Allocation {}
 unsafe impl Sync for Allocation {}
 
 /// Parameters of `Allocation` objects, that can be retrieved using `Allocator::get_allocation_info`.
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Clone)]
 pub struct AllocationInfo {
     /// Pointer to internal VmaAllocationInfo instance
     internal: ffi::VmaAllocationInfo,
@@ -99,6 +261,19 @@ pub struct AllocationInfo {
 unsafe impl Send for AllocationInfo {}
 unsafe impl Sync for AllocationInfo {}
 
+impl std::fmt::Debug for AllocationInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AllocationInfo")
+            .field("memory_type", &self.get_memory_type())
+            .field("device_memory", &self.get_device_memory())
+            .field("offset", &self.get_offset())
+            .field("size", &self.get_size())
+            .field("mapped", &self.mapped_ptr().is_some())
+            .field("name", &self.get_name())
+            .finish()
+    }
+}
+
 impl AllocationInfo {
     #[inline(always)]
     // Gets the memory type index that this allocation was allocated from. (Never changes)
@@ -137,6 +312,24 @@ impl AllocationInfo {
         self.internal.size as erupt::vk::DeviceSize
     }
 
+    /// Range of bytes occupied by this allocation in its device memory object, i.e.
+    /// `get_offset()..get_offset() + get_size()`.
+    #[inline(always)]
+    pub fn byte_range(&self) -> std::ops::Range<erupt::vk::DeviceSize> {
+        self.get_offset()..self.get_offset() + self.get_size()
+    }
+
+    /// The device memory object of this allocation together with `AllocationInfo::byte_range`.
+    #[inline(always)]
+    pub fn memory_range(
+        &self,
+    ) -> (
+        erupt::vk::DeviceMemory,
+        std::ops::Range<erupt::vk::DeviceSize>,
+    ) {
+        (self.get_device_memory(), self.byte_range())
+    }
+
     /// Pointer to the beginning of this allocation as mapped data.
     ///
     /// If the allocation hasn't been mapped using `Allocator::map_memory` and hasn't been
@@ -150,6 +343,25 @@ impl AllocationInfo {
         self.internal.pMappedData as *mut u8
     }
 
+    /// Pointer to the beginning of this allocation as mapped data, or `None` if it isn't mapped.
+    ///
+    /// Same as `AllocationInfo::get_mapped_data`, but makes the unmapped case explicit.
+    #[inline(always)]
+    pub fn mapped_ptr(&self) -> Option<std::ptr::NonNull<u8>> {
+        std::ptr::NonNull::new(self.get_mapped_data())
+    }
+
+    /// Returns true if the allocation was mapped when this information was retrieved, which is
+    /// always the case for allocations created with `AllocationCreateFlags::MAPPED` in
+    /// `HOST_VISIBLE` memory.
+    ///
+    /// `AllocationCreateFlags::MAPPED` is ignored for memory types that aren't `HOST_VISIBLE`,
+    /// so this returns false for such allocations and they can't be written directly.
+    #[inline(always)]
+    pub fn is_persistently_mapped(&self) -> bool {
+        !self.internal.pMappedData.is_null()
+    }
+
     /*#[inline(always)]
     pub fn get_mapped_slice(&self) -> Option<&mut &[u8]> {
         if self.internal.pMappedData.is_null() {
@@ -167,14 +379,44 @@ impl AllocationInfo {
         self.internal.pUserData
     }
 
+    /// Value that was passed as `AllocationCreateInfo::user_data_u64`, or the user data pointer
+    /// reinterpreted as an integer if it was set another way.
+    ///
+    /// On 32-bit targets only the lower 32 bits of the original value are available.
+    #[inline(always)]
+    pub fn get_user_data_u64(&self) -> u64 {
+        self.internal.pUserData as usize as u64
+    }
+
+    /// Tag set with `Allocator::set_allocation_tag`, or passed as
+    /// `AllocationCreateInfo::user_data_u64`.
+    ///
+    /// Only the lower 32 bits of the user data are returned, so the value is meaningless if the
+    /// user data was set to a pointer.
+    #[inline(always)]
+    pub fn allocation_tag(&self) -> u32 {
+        self.get_user_data_u64() as u32
+    }
+
+    /// Custom allocation name that was set with `vmaSetAllocationName`, or `None` if the allocation is unnamed.
+    ///
+    /// It can change after another call to `vmaSetAllocationName` for the same allocation.
     #[inline(always)]
-    pub fn get_name(&self) -> &std::ffi::CStr {
+    pub fn get_name(&self) -> Option<&std::ffi::CStr> {
         if self.internal.pName.is_null() {
-            Default::default()
+            None
         } else {
-            unsafe { std::ffi::CStr::from_ptr(self.internal.pName) }
+            Some(unsafe { std::ffi::CStr::from_ptr(self.internal.pName) })
         }
     }
+
+    /// Same as `AllocationInfo::get_name`, but as a string slice.
+    ///
+    /// Returns `None` if the allocation is unnamed or its name is not valid UTF-8.
+    #[inline(always)]
+    pub fn get_name_str(&self) -> Option<&str> {
+        self.get_name().and_then(|name| name.to_str().ok())
+    }
 }
 
 bitflags! {
@@ -184,6 +426,15 @@ bitflags! {
         /// Defaults
         const NONE = 0;
 
+        /// Allocator and all objects created from it will not be synchronized internally,
+        /// so you must guarantee they are used from only one thread at a time or synchronized
+        /// externally by you.
+        ///
+        /// Using this flag may increase performance because internal mutexes are not used.
+        ///
+        /// `Allocator::new` rejects this flag, use `LocalAllocator::new` instead.
+        const EXTERNALLY_SYNCHRONIZED = ffi::VmaAllocatorCreateFlagBits_VMA_ALLOCATOR_CREATE_EXTERNALLY_SYNCHRONIZED_BIT;
+
         /// Enables usage of VK_KHR_dedicated_allocation extension.
         ///
         /// The flag works only if VmaAllocatorCreateInfo::vulkanApiVersion `== VK_API_VERSION_1_0`.
@@ -288,6 +539,36 @@ impl Default for AllocatorCreateFlags {
     }
 }
 
+/// Signature of a closure called by `DeviceMemoryCallbacks`.
+///
+/// Receives the memory type index, the `erupt::vk::DeviceMemory` handle and the size of the block.
+pub type DeviceMemoryCallback =
+    dyn Fn(u32, erupt::vk::DeviceMemory, erupt::vk::DeviceSize) + Send + Sync;
+
+/// Set of callbacks that the library will call for `vkAllocateMemory` and `vkFreeMemory`.
+///
+/// Provided for informative purpose, e.g. to gather statistics about number of
+/// allocations or total amount of memory allocated in Vulkan.
+///
+/// The closures may be called from any thread that uses the allocator and must not panic.
+#[derive(Default)]
+pub struct DeviceMemoryCallbacks {
+    /// Called after successful `vkAllocateMemory`. Optional.
+    pub allocate: Option<Box<DeviceMemoryCallback>>,
+
+    /// Called before `vkFreeMemory`. Optional.
+    pub free: Option<Box<DeviceMemoryCallback>>,
+}
+
+impl std::fmt::Debug for DeviceMemoryCallbacks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeviceMemoryCallbacks")
+            .field("allocate", &self.allocate.is_some())
+            .field("free", &self.free.is_some())
+            .finish()
+    }
+}
+
 /// Description of an `Allocator` to be created.
 pub struct AllocatorCreateInfo {
     /// Vulkan physical device. It must be valid throughout whole lifetime of created allocator.
@@ -307,20 +588,29 @@ pub struct AllocatorCreateInfo {
     pub preferred_large_heap_block_size: usize,
 
     /// Custom CPU memory allocation callbacks. Optional. Will be used for all CPU-side allocations.
+    ///
+    /// A copy of the callbacks is kept inside the created `Allocator`, so this struct doesn't need to
+    /// outlive it, but `user_data` must stay valid for the whole lifetime of the allocator.
+    ///
+    /// `pfn_allocation`, `pfn_reallocation` and `pfn_free` must be sound C callbacks following the Vulkan
+    /// rules for `VkAllocationCallbacks`: they may be called from multiple threads, must return memory
+    /// aligned as requested and must never unwind across the FFI boundary.
     pub allocation_callbacks: Option<erupt::vk::AllocationCallbacks>,
 
     /// Informative callbacks for `vkAllocateMemory`, `vkFreeMemory`. Optional.
-    pub device_memory_callbacks: Option<ffi::VmaDeviceMemoryCallbacks>,
+    ///
+    /// The created `Allocator` keeps a reference to the callbacks until it is destroyed.
+    pub device_memory_callbacks: Option<Arc<DeviceMemoryCallbacks>>,
 
     /// Either empty or an array of limits on maximum number of bytes that can be allocated
     /// out of particular Vulkan memory heap.
     ///
     /// If not empty, it must contain `erupt::vk::PhysicalDeviceMemoryProperties::memory_heap_count` elements,
     /// defining limit on maximum number of bytes that can be allocated out of particular Vulkan
-    /// memory heap.
+    /// memory heap. Otherwise `Allocator::new` fails with `ErrorKind::Config`.
     ///
     /// Any of the elements may be equal to `erupt::vk::WHOLE_SIZE`, which means no limit on that
-    /// heap. This is also the default in case of an empty slice.
+    /// heap. This is also the default for `None` and for an empty `Vec`.
     ///
     /// If there is a limit defined for a heap:
     ///
@@ -347,37 +637,84 @@ pub struct AllocatorCreateInfo {
     pub vulkan_api_version: u32,
 }
 
-// /// Construct `AllocatorCreateInfo` with default values
-// ///
-// /// Note that the default `device` and `instance` fields are filled with dummy
-// /// implementations that will panic if used. These fields must be overwritten.
-// impl Default for AllocatorCreateInfo {
-//     fn default() -> Self {
-//         extern "C" fn get_device_proc_addr(
-//             _: erupt::vk::Instance,
-//             _: *const std::os::raw::c_char,
-//         ) -> *const std::os::raw::c_void {
-//             std::ptr::null()
-//         }
-//         extern "C" fn get_instance_proc_addr(
-//             _: erupt::vk::Instance,
-//             _: *const std::os::raw::c_char,
-//         ) -> *const std::os::raw::c_void {
-//             get_device_proc_addr as *const _
-//         }
-//         let instance = Arc<>;
-//         let device = unsafe { Arc::new(DeviceLoader::) };
-//         AllocatorCreateInfo {
-//             physical_device: erupt::vk::PhysicalDevice::null(),
-//             device,
-//             instance,
-//             flags: AllocatorCreateFlags::NONE,
-//             preferred_large_heap_block_size: 0,
-//             frame_in_use_count: 0,
-//             heap_size_limits: None,
-//         }
-//     }
-// }
+impl AllocatorCreateInfo {
+    /// Creates a description of an `Allocator` for the given Vulkan handles.
+    ///
+    /// All other fields are left at their defaults: no flags, default block size,
+    /// no heap size limits, no callbacks and Vulkan 1.0.
+    pub fn new(
+        physical_device: erupt::vk::PhysicalDevice,
+        device: Arc<erupt::DeviceLoader>,
+        instance: Arc<erupt::InstanceLoader>,
+    ) -> Self {
+        AllocatorCreateInfo {
+            physical_device,
+            device,
+            instance,
+            flags: AllocatorCreateFlags::NONE,
+            preferred_large_heap_block_size: 0,
+            allocation_callbacks: None,
+            device_memory_callbacks: None,
+            heap_size_limits: None,
+            vulkan_api_version: 0,
+        }
+    }
+
+    /// Sets `AllocatorCreateInfo::flags`.
+    pub fn flags(mut self, flags: AllocatorCreateFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Sets `AllocatorCreateInfo::preferred_large_heap_block_size`.
+    pub fn preferred_large_heap_block_size(mut self, size: usize) -> Self {
+        self.preferred_large_heap_block_size = size;
+        self
+    }
+
+    /// Sets `AllocatorCreateInfo::allocation_callbacks`.
+    pub fn allocation_callbacks(mut self, callbacks: erupt::vk::AllocationCallbacks) -> Self {
+        self.allocation_callbacks = Some(callbacks);
+        self
+    }
+
+    /// Sets `AllocatorCreateInfo::device_memory_callbacks`.
+    pub fn device_memory_callbacks(mut self, callbacks: Arc<DeviceMemoryCallbacks>) -> Self {
+        self.device_memory_callbacks = Some(callbacks);
+        self
+    }
+
+    /// Sets `AllocatorCreateInfo::heap_size_limits`.
+    pub fn heap_size_limits(mut self, limits: Vec<erupt::vk::DeviceSize>) -> Self {
+        self.heap_size_limits = Some(limits);
+        self
+    }
+
+    /// Sets `AllocatorCreateInfo::vulkan_api_version`.
+    pub fn vulkan_api_version(mut self, version: u32) -> Self {
+        self.vulkan_api_version = version;
+        self
+    }
+}
+
+/// Information about existing `Allocator` object, returned by `Allocator::get_allocator_info`.
+#[derive(Debug, Copy, Clone)]
+pub struct AllocatorInfo {
+    /// Handle to Vulkan instance object.
+    ///
+    /// This is the same value as has been passed through `AllocatorCreateInfo::instance`.
+    pub instance: erupt::vk::Instance,
+
+    /// Handle to Vulkan physical device object.
+    ///
+    /// This is the same value as has been passed through `AllocatorCreateInfo::physical_device`.
+    pub physical_device: erupt::vk::PhysicalDevice,
+
+    /// Handle to Vulkan device object.
+    ///
+    /// This is the same value as has been passed through `AllocatorCreateInfo::device`.
+    pub device: erupt::vk::Device,
+}
 
 /// Converts a raw result into an erupt result.
 #[inline]
@@ -385,6 +722,242 @@ fn ffi_to_result(result: ffi::VkResult) -> erupt::vk::Result {
     erupt::vk::Result(result)
 }
 
+/// Forwards `pfnAllocate` of `VmaDeviceMemoryCallbacks` to `DeviceMemoryCallbacks::allocate`.
+unsafe extern "C" fn device_memory_allocate_callback(
+    _allocator: ffi::VmaAllocator,
+    memory_type: u32,
+    memory: ffi::VkDeviceMemory,
+    size: ffi::VkDeviceSize,
+    user_data: *mut ::std::os::raw::c_void,
+) {
+    let callbacks = &*(user_data as *const DeviceMemoryCallbacks);
+    if let Some(allocate) = &callbacks.allocate {
+        allocate(memory_type, erupt::vk::DeviceMemory(memory as u64), size);
+    }
+}
+
+/// Forwards `pfnFree` of `VmaDeviceMemoryCallbacks` to `DeviceMemoryCallbacks::free`.
+unsafe extern "C" fn device_memory_free_callback(
+    _allocator: ffi::VmaAllocator,
+    memory_type: u32,
+    memory: ffi::VkDeviceMemory,
+    size: ffi::VkDeviceSize,
+    user_data: *mut ::std::os::raw::c_void,
+) {
+    let callbacks = &*(user_data as *const DeviceMemoryCallbacks);
+    if let Some(free) = &callbacks.free {
+        free(memory_type, erupt::vk::DeviceMemory(memory as u64), size);
+    }
+}
+
+/// Rejects image descriptions that VMA asserts on instead of reporting an error.
+fn validate_image_create_info(info: &erupt::vk::ImageCreateInfo) -> Result<()> {
+    let extent = info.extent;
+    if extent.width == 0
+        || extent.height == 0
+        || extent.depth == 0
+        || info.mip_levels == 0
+        || info.array_layers == 0
+    {
+        return Err(Error::vulkan(
+            erupt::vk::Result::ERROR_VALIDATION_FAILED_EXT,
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects null allocations, which VMA asserts on instead of reporting an error.
+fn check_allocation(allocation: &Allocation) -> Result<()> {
+    if allocation.is_null() {
+        return Err(Error::config("the allocation is null"));
+    }
+    Ok(())
+}
+
+/// Fetches the properties of the physical device that `internal` was created for.
+///
+/// `internal` must be a valid allocator.
+unsafe fn device_properties_of(
+    internal: ffi::VmaAllocator,
+) -> (
+    erupt::vk::PhysicalDeviceProperties,
+    erupt::vk::PhysicalDeviceMemoryProperties,
+) {
+    let mut ffi_properties: *const ffi::VkPhysicalDeviceProperties = std::ptr::null();
+    ffi::vmaGetPhysicalDeviceProperties(internal, &mut ffi_properties);
+    let mut ffi_memory_properties: *const ffi::VkPhysicalDeviceMemoryProperties = std::ptr::null();
+    ffi::vmaGetMemoryProperties(internal, &mut ffi_memory_properties);
+    (
+        mem::transmute::<ffi::VkPhysicalDeviceProperties, erupt::vk::PhysicalDeviceProperties>(
+            *ffi_properties,
+        ),
+        mem::transmute::<
+            ffi::VkPhysicalDeviceMemoryProperties,
+            erupt::vk::PhysicalDeviceMemoryProperties,
+        >(*ffi_memory_properties),
+    )
+}
+
+/// Returns the `erupt::vk::MemoryDedicatedRequirements` in the `p_next` chain of `requirements`, if any.
+///
+/// Every structure in the chain must be valid.
+unsafe fn dedicated_requirements(
+    requirements: &erupt::vk::MemoryRequirements2,
+) -> Option<erupt::vk::MemoryDedicatedRequirements> {
+    let mut next = requirements.p_next as *const erupt::vk::BaseOutStructure;
+    while let Some(structure) = next.as_ref() {
+        if structure.s_type == erupt::vk::StructureType::MEMORY_DEDICATED_REQUIREMENTS {
+            return Some(*(next as *const erupt::vk::MemoryDedicatedRequirements));
+        }
+        next = structure.p_next as *const _;
+    }
+    None
+}
+
+/// Maps the `ERROR_FEATURE_NOT_PRESENT` result of the `find_memory_type_index*` functions to `None`.
+fn memory_type_index_if_found(result: Result<u32>) -> Result<Option<u32>> {
+    match result {
+        Ok(memory_type_index) => Ok(Some(memory_type_index)),
+        Err(err) => match err.kind() {
+            ErrorKind::Vulkan(erupt::vk::Result::ERROR_FEATURE_NOT_PRESENT) => Ok(None),
+            _ => Err(err),
+        },
+    }
+}
+
+// Physical device and memory properties returned by VMA are transmuted into the erupt types,
+// so a layout mismatch between the bindings and erupt has to fail the build.
+const _: () = assert!(
+    mem::size_of::<ffi::VkPhysicalDeviceProperties>()
+        == mem::size_of::<erupt::vk::PhysicalDeviceProperties>()
+        && mem::align_of::<ffi::VkPhysicalDeviceProperties>()
+            == mem::align_of::<erupt::vk::PhysicalDeviceProperties>()
+);
+const _: () = assert!(
+    mem::size_of::<ffi::VkPhysicalDeviceMemoryProperties>()
+        == mem::size_of::<erupt::vk::PhysicalDeviceMemoryProperties>()
+        && mem::align_of::<ffi::VkPhysicalDeviceMemoryProperties>()
+            == mem::align_of::<erupt::vk::PhysicalDeviceMemoryProperties>()
+);
+// Buffer and image create infos are converted field by field, so a size mismatch means the
+// bindings and erupt disagree about the fields and has to fail the build as well.
+const _: () = assert!(
+    mem::size_of::<ffi::VkBufferCreateInfo>() == mem::size_of::<erupt::vk::BufferCreateInfo>()
+);
+const _: () = assert!(
+    mem::size_of::<ffi::VkImageCreateInfo>() == mem::size_of::<erupt::vk::ImageCreateInfo>()
+);
+
+// Statistics are plain data meant to be sent to other threads, e.g. for telemetry, so adding a
+// field that isn't `Send` or `Sync` has to fail the build.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Statistics>();
+    assert_send_sync::<DetailedStatistics>();
+    assert_send_sync::<TotalStatistics>();
+    assert_send_sync::<Budget>();
+};
+
+/// Returns a copy of `allocation_info` requesting a dedicated allocation with `priority`.
+fn dedicated_with_priority(
+    allocation_info: &AllocationCreateInfo,
+    priority: f32,
+) -> AllocationCreateInfo {
+    AllocationCreateInfo {
+        flags: allocation_info.flags | AllocationCreateFlags::DEDICATED_MEMORY,
+        priority,
+        ..allocation_info.clone()
+    }
+}
+
+/// Converts raw `VmaStatistics` into `Statistics`.
+fn statistics_from_ffi(stats: &ffi::VmaStatistics) -> Statistics {
+    Statistics {
+        block_count: stats.blockCount,
+        allocation_count: stats.allocationCount,
+        block_bytes: stats.blockBytes,
+        allocation_bytes: stats.allocationBytes,
+    }
+}
+
+/// Converts a raw `VmaBudget` into `Budget`.
+fn budget_from_ffi(budget: &ffi::VmaBudget) -> Budget {
+    Budget {
+        statistics: statistics_from_ffi(&budget.statistics),
+        usage: budget.usage,
+        budget: budget.budget,
+    }
+}
+
+/// VMA reports the minimum of an empty set of sizes as `VK_WHOLE_SIZE`.
+fn size_min_from_ffi(size: erupt::vk::DeviceSize) -> Option<erupt::vk::DeviceSize> {
+    if size == erupt::vk::WHOLE_SIZE {
+        None
+    } else {
+        Some(size)
+    }
+}
+
+/// Converts raw `VmaDetailedStatistics` into `DetailedStatistics`.
+fn detailed_statistics_from_ffi(stats: &ffi::VmaDetailedStatistics) -> DetailedStatistics {
+    DetailedStatistics {
+        block_count: stats.statistics.blockCount,
+        allocation_count: stats.statistics.allocationCount,
+        block_bytes: stats.statistics.blockBytes,
+        allocation_bytes: stats.statistics.allocationBytes,
+        unused_range_count: stats.unusedRangeCount,
+        allocation_size_min: size_min_from_ffi(stats.allocationSizeMin),
+        allocation_size_max: stats.allocationSizeMax,
+        unused_range_size_min: size_min_from_ffi(stats.unusedRangeSizeMin),
+        unused_range_size_max: stats.unusedRangeSizeMax,
+    }
+}
+
+/// Converts an `erupt::vk::BufferCreateInfo` struct into the raw representation.
+///
+/// The `p_next` chain and `p_queue_family_indices` are forwarded as pointers, so whatever they
+/// point to must stay alive until the VMA call consuming the result returns.
+fn buffer_create_info_to_ffi(info: &erupt::vk::BufferCreateInfo) -> ffi::VkBufferCreateInfo {
+    ffi::VkBufferCreateInfo {
+        sType: info.s_type.0 as ffi::VkStructureType,
+        pNext: info.p_next,
+        flags: info.flags.bits(),
+        size: info.size,
+        usage: info.usage.bits(),
+        sharingMode: info.sharing_mode.0 as ffi::VkSharingMode,
+        queueFamilyIndexCount: info.queue_family_index_count,
+        pQueueFamilyIndices: info.p_queue_family_indices,
+    }
+}
+
+/// Converts an `erupt::vk::ImageCreateInfo` struct into the raw representation.
+///
+/// The `p_next` chain and `p_queue_family_indices` are forwarded as pointers, so whatever they
+/// point to must stay alive until the VMA call consuming the result returns.
+fn image_create_info_to_ffi(info: &erupt::vk::ImageCreateInfo) -> ffi::VkImageCreateInfo {
+    ffi::VkImageCreateInfo {
+        sType: info.s_type.0 as ffi::VkStructureType,
+        pNext: info.p_next,
+        flags: info.flags.bits(),
+        imageType: info.image_type.0 as ffi::VkImageType,
+        format: info.format.0 as ffi::VkFormat,
+        extent: ffi::VkExtent3D {
+            width: info.extent.width,
+            height: info.extent.height,
+            depth: info.extent.depth,
+        },
+        mipLevels: info.mip_levels,
+        arrayLayers: info.array_layers,
+        samples: info.samples.0 as ffi::VkSampleCountFlagBits,
+        tiling: info.tiling.0 as ffi::VkImageTiling,
+        usage: info.usage.bits(),
+        sharingMode: info.sharing_mode.0 as ffi::VkSharingMode,
+        queueFamilyIndexCount: info.queue_family_index_count,
+        pQueueFamilyIndices: info.p_queue_family_indices,
+        initialLayout: info.initial_layout.0 as ffi::VkImageLayout,
+    }
+}
+
 /// Converts an `AllocationCreateInfo` struct into the raw representation.
 fn allocation_create_info_to_ffi(info: &AllocationCreateInfo) -> ffi::VmaAllocationCreateInfo {
     ffi::VmaAllocationCreateInfo {
@@ -397,7 +970,11 @@ fn allocation_create_info_to_ffi(info: &AllocationCreateInfo) -> ffi::VmaAllocat
             Some(pool) => pool.internal,
             None => std::ptr::null_mut(),
         },
-        pUserData: info.user_data.unwrap_or(::std::ptr::null_mut()),
+        pUserData: match (info.user_data, info.user_data_u64) {
+            (Some(user_data), _) => user_data,
+            (None, Some(user_data)) => user_data as usize as *mut ::std::os::raw::c_void,
+            (None, None) => ::std::ptr::null_mut(),
+        },
         priority: info.priority,
     }
 }
@@ -412,7 +989,7 @@ fn pool_create_info_to_ffi(info: &AllocatorPoolCreateInfo) -> ffi::VmaPoolCreate
         maxBlockCount: info.max_block_count,
         minAllocationAlignment: info.min_allocation_alignment,
         priority: info.priority,
-        pMemoryAllocateNext: std::ptr::null_mut(),
+        pMemoryAllocateNext: info.memory_allocate_next.unwrap_or(std::ptr::null_mut()),
     }
 }
 
@@ -527,6 +1104,68 @@ impl Default for MemoryUsage {
     }
 }
 
+impl MemoryUsage {
+    /// Returns the name of the corresponding `VmaMemoryUsage` value, e.g. `"VMA_MEMORY_USAGE_AUTO"`.
+    ///
+    /// Unlike matching on `MemoryUsage` directly, this doesn't trigger deprecation warnings for
+    /// the obsolete variants.
+    #[allow(deprecated)]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MemoryUsage::Unknown => "VMA_MEMORY_USAGE_UNKNOWN",
+            MemoryUsage::GpuOnly => "VMA_MEMORY_USAGE_GPU_ONLY",
+            MemoryUsage::CpuOnly => "VMA_MEMORY_USAGE_CPU_ONLY",
+            MemoryUsage::CpuToGpu => "VMA_MEMORY_USAGE_CPU_TO_GPU",
+            MemoryUsage::GpuToCpu => "VMA_MEMORY_USAGE_GPU_TO_CPU",
+            MemoryUsage::CpuCopy => "VMA_MEMORY_USAGE_CPU_COPY",
+            MemoryUsage::GpuLazilyAllocated => "VMA_MEMORY_USAGE_GPU_LAZILY_ALLOCATED",
+            MemoryUsage::Auto => "VMA_MEMORY_USAGE_AUTO",
+            MemoryUsage::AutoPreferDevice => "VMA_MEMORY_USAGE_AUTO_PREFER_DEVICE",
+            MemoryUsage::AutoPreferHost => "VMA_MEMORY_USAGE_AUTO_PREFER_HOST",
+        }
+    }
+
+    /// Returns true for the `MemoryUsage::Auto*` values, with which an allocation can only be
+    /// mapped if it was created with `AllocationCreateFlags::HOST_ACCESS_SEQUENTIAL_WRITE` or
+    /// `AllocationCreateFlags::HOST_ACCESS_RANDOM`.
+    pub fn requires_host_access_flag(&self) -> bool {
+        matches!(
+            self,
+            MemoryUsage::Auto | MemoryUsage::AutoPreferDevice | MemoryUsage::AutoPreferHost
+        )
+    }
+}
+
+impl std::fmt::Display for MemoryUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Converts a raw `VmaMemoryUsage` value, returning it back as the error if it's unknown.
+impl TryFrom<u32> for MemoryUsage {
+    type Error = u32;
+
+    #[allow(deprecated)]
+    fn try_from(value: u32) -> std::result::Result<Self, Self::Error> {
+        [
+            Self::Unknown,
+            Self::GpuOnly,
+            Self::CpuOnly,
+            Self::CpuToGpu,
+            Self::GpuToCpu,
+            Self::CpuCopy,
+            Self::GpuLazilyAllocated,
+            Self::Auto,
+            Self::AutoPreferDevice,
+            Self::AutoPreferHost,
+        ]
+        .into_iter()
+        .find(|usage| *usage as u32 == value)
+        .ok_or(value)
+    }
+}
+
 bitflags! {
     /// Flags for configuring `AllocatorPool` construction.
     pub struct AllocatorPoolCreateFlags: u32 {
@@ -742,9 +1381,10 @@ pub struct AllocationCreateInfo {
     ///
     /// Value 0 is equivalent to `std::u32::MAX` - it means any memory type is accepted if
     /// it meets other requirements specified by this structure, with no further restrictions
-    /// on memory type index.
+    /// on memory type index. `AllocationCreateInfo::any_memory_type` and
+    /// `AllocationCreateInfo::restrict_to_types` set it explicitly.
     ///
-    /// If `pool` is not `None`, this member is ignored.
+    /// If `pool` is not `None`, this member is ignored, and setting it is rejected in debug builds.
     pub memory_type_bits: u32,
 
     /// Pool that this allocation should be created in.
@@ -761,14 +1401,73 @@ pub struct AllocationCreateInfo {
     /// doesn't need to be valid after allocation call.
     pub user_data: Option<*mut ::std::os::raw::c_void>,
 
+    /// Integer stored in place of the `user_data` pointer, e.g. an index or ID of the resource,
+    /// which can be read back with `AllocationInfo::get_user_data_u64()`.
+    ///
+    /// Ignored if `user_data` is `Some`. Must not be combined with
+    /// `AllocationCreateFlags::USER_DATA_COPY_STRING`.
+    ///
+    /// VMA stores a pointer-sized value, so on 32-bit targets only the lower 32 bits are kept.
+    pub user_data_u64: Option<u64>,
+
     /// A floating-point value between 0 and 1, indicating the priority of the allocation relative to other memory allocations.
     ///
     /// It is used only when #VMA_ALLOCATOR_CREATE_EXT_MEMORY_PRIORITY_BIT flag was used during creation of the #VmaAllocator object
     /// and this allocation ends up as dedicated or is explicitly forced as dedicated using #VMA_ALLOCATION_CREATE_DEDICATED_MEMORY_BIT.
     /// Otherwise, it has the priority of a memory block where it is placed and this variable is ignored.
+    ///
+    /// A value outside of [0.0, 1.0] is rejected with an error of kind `ErrorKind::Config`.
+    /// A non-zero value without the flag is logged with `log::warn!` when the `log` feature is
+    /// enabled, or rejected with an error of kind `ErrorKind::Config` when the `strict_priority`
+    /// feature is enabled.
     pub priority: f32,
 }
 
+impl AllocationCreateInfo {
+    /// Accepts any memory type that meets the other requirements, by setting `memory_type_bits`
+    /// to 0.
+    pub fn any_memory_type(mut self) -> Self {
+        self.memory_type_bits = 0;
+        self
+    }
+
+    /// Only accepts memory types whose bits are set in `memory_type_bits`, e.g. a mask built with
+    /// the free function `memory_type_bits`.
+    ///
+    /// A mask of 0 is the same as `AllocationCreateInfo::any_memory_type`.
+    pub fn restrict_to_types(mut self, memory_type_bits: u32) -> Self {
+        self.memory_type_bits = memory_type_bits;
+        self
+    }
+
+    /// Persistently mapped memory that the host fills sequentially and the device reads, e.g. a
+    /// staging buffer for uploads or uniform data updated every frame.
+    ///
+    /// Uses `MemoryUsage::Auto` with `AllocationCreateFlags::HOST_ACCESS_SEQUENTIAL_WRITE` and
+    /// `AllocationCreateFlags::MAPPED`.
+    pub fn for_mapped_upload() -> Self {
+        AllocationCreateInfo {
+            usage: MemoryUsage::Auto,
+            flags: AllocationCreateFlags::HOST_ACCESS_SEQUENTIAL_WRITE
+                | AllocationCreateFlags::MAPPED,
+            ..Default::default()
+        }
+    }
+
+    /// Persistently mapped memory that the device writes and the host reads back, e.g. for
+    /// screenshots or query results.
+    ///
+    /// Uses `MemoryUsage::Auto` with `AllocationCreateFlags::HOST_ACCESS_RANDOM` and
+    /// `AllocationCreateFlags::MAPPED`, so a `HOST_CACHED` memory type is chosen.
+    pub fn for_mapped_readback() -> Self {
+        AllocationCreateInfo {
+            usage: MemoryUsage::Auto,
+            flags: AllocationCreateFlags::HOST_ACCESS_RANDOM | AllocationCreateFlags::MAPPED,
+            ..Default::default()
+        }
+    }
+}
+
 /// Description of an `AllocationPool` to be created.
 #[derive(Default, Debug, Clone)]
 pub struct AllocatorPoolCreateInfo {
@@ -804,7 +1503,8 @@ pub struct AllocatorPoolCreateInfo {
     /// A floating-point value between 0 and 1, indicating the priority of the allocations in this pool relative to other memory allocations.
     ///
     /// It is used only when #VMA_ALLOCATOR_CREATE_EXT_MEMORY_PRIORITY_BIT flag was used during creation of the #VmaAllocator object.
-    /// Otherwise, this variable is ignored.
+    /// Otherwise, this variable is ignored, and a non-zero value is reported the same way as
+    /// `AllocationCreateInfo::priority`.
     pub priority: f32,
 
     /// \brief Additional minimum alignment to be used for all allocations created from this pool. Can be 0.
@@ -813,6 +1513,18 @@ pub struct AllocatorPoolCreateInfo {
     /// It can be useful in cases where alignment returned by Vulkan by functions like `vkGetBufferMemoryRequirements` is not enough,
     /// e.g. when doing interop with OpenGL.
     pub min_allocation_alignment: erupt::vk::DeviceSize,
+
+    /// Additional `pNext` chain to be attached to `erupt::vk::MemoryAllocateInfo` used for every
+    /// allocation made by this pool. Optional.
+    ///
+    /// It can be useful for special needs such as adding `erupt::vk::MemoryAllocateFlagsInfo`
+    /// with `erupt::vk::MemoryAllocateFlags::DEVICE_ADDRESS`, so that buffers created in this pool
+    /// with `erupt::vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS` can have their address queried,
+    /// or `erupt::vk::ExportMemoryAllocateInfo` for exporting memory.
+    ///
+    /// The library does not copy the chain - the structures it points to must remain alive and
+    /// unchanged until the pool is destroyed, as new memory blocks may be allocated at any time.
+    pub memory_allocate_next: Option<*mut ::std::os::raw::c_void>,
 }
 
 bitflags! {
@@ -888,7 +1600,7 @@ pub struct DefragmentationContext {
 
 /// Parameters for defragmentation.
 ///
-/// To be used with function `Allocator::defragmentation_begin`.
+/// To be used with functions `Allocator::begin_defragmentation` and `Allocator::defragment`.
 #[derive(Debug, Clone)]
 pub struct DefragmentationInfo {
     /// See `DefragmentationFlags`
@@ -925,24 +1637,42 @@ pub struct DefragmentationMove {
     pub dst_tmp_allocation: Allocation,
 }
 
-pub enum DefragmentationPassResult {
+impl DefragmentationMove {
+    /// Returns information about `dst_tmp_allocation`, i.e. the memory and offset a new buffer or
+    /// image has to be bound to.
+    pub fn dst_info<S>(&self, allocator: &Allocator<S>) -> Result<AllocationInfo> {
+        allocator.get_allocation_info(&self.dst_tmp_allocation)
+    }
+
+    /// Returns information about `src_allocation`, i.e. the current place of the allocation.
+    pub fn src_info<S>(&self, allocator: &Allocator<S>) -> Result<AllocationInfo> {
+        allocator.get_allocation_info(&self.src_allocation)
+    }
+}
+
+pub enum DefragmentationPassResult<'a> {
     /// No more moves are possible. You can omit call to vmaEndDefragmentationPass() and simply end whole defragmentation.
     Success,
     /// There are pending moves returned. You need to perform them, call vmaEndDefragmentationPass(), and then preferably try another pass with vmaBeginDefragmentationPass().
-    Incomplete(DefragmentationPassMoveInfo),
+    Incomplete(DefragmentationPassMoveInfo<'a>),
 }
 
 /// Parameters for incremental defragmentation steps.
 ///
-/// To be used with function `Allocator::defragmentation_begin_pass`.
-#[derive(Debug, Clone)]
-pub struct DefragmentationPassMoveInfo {
+/// To be used with function `Allocator::begin_defragmentation_pass`.
+///
+/// It mutably borrows the `DefragmentationContext` the pass was started on, so another pass
+/// can't be started, and defragmentation can't be ended, until the moves are passed to
+/// `Allocator::end_defragmentation_pass` and dropped.
+#[derive(Debug)]
+pub struct DefragmentationPassMoveInfo<'a> {
+    context: &'a mut DefragmentationContext,
     internal: ffi::VmaDefragmentationPassMoveInfo,
     moves: Box<[DefragmentationMove]>,
 }
 
 // Slice accessors that prevent changing the array length
-impl DefragmentationPassMoveInfo {
+impl DefragmentationPassMoveInfo<'_> {
     pub fn moves(&self) -> &[DefragmentationMove] {
         &self.moves
     }
@@ -951,7 +1681,7 @@ impl DefragmentationPassMoveInfo {
     }
 }
 
-/// Statistics returned by `Allocator::defragment`
+/// Statistics returned by `Allocator::end_defragmentation` and `Allocator::defragment`
 #[derive(Debug, Copy, Clone)]
 pub struct DefragmentationStats {
     /// Total number of bytes that have been copied while moving allocations to different places.
@@ -967,58 +1697,314 @@ pub struct DefragmentationStats {
     pub device_memory_blocks_freed: u32,
 }
 
-impl Allocator {
-    /// Constructor a new `Allocator` using the provided options.
-    pub fn new(create_info: &AllocatorCreateInfo) -> Result<Self> {
-        let instance = create_info.instance.clone();
-        let device = create_info.device.clone();
-        let routed_functions = unsafe {
-            ffi::VmaVulkanFunctions {
-                vkGetInstanceProcAddr: mem::transmute::<_, ffi::PFN_vkGetInstanceProcAddr>(
-                    instance.get_instance_proc_addr,
-                ),
-                vkGetDeviceProcAddr: mem::transmute::<_, ffi::PFN_vkGetDeviceProcAddr>(
-                    instance.get_device_proc_addr,
-                ),
-                vkGetPhysicalDeviceProperties: mem::transmute::<
-                    _,
-                    ffi::PFN_vkGetPhysicalDeviceProperties,
-                >(
-                    instance.get_physical_device_properties
-                ),
-                vkGetPhysicalDeviceMemoryProperties: mem::transmute::<
-                    _,
-                    ffi::PFN_vkGetPhysicalDeviceMemoryProperties,
-                >(
-                    instance.get_physical_device_memory_properties,
-                ),
-                vkAllocateMemory: mem::transmute::<_, ffi::PFN_vkAllocateMemory>(
-                    device.allocate_memory,
-                ),
-                vkFreeMemory: mem::transmute::<_, ffi::PFN_vkFreeMemory>(device.free_memory),
-                vkMapMemory: mem::transmute::<_, ffi::PFN_vkMapMemory>(device.map_memory),
-                vkUnmapMemory: mem::transmute::<_, ffi::PFN_vkUnmapMemory>(device.unmap_memory),
-                vkFlushMappedMemoryRanges: mem::transmute::<_, ffi::PFN_vkFlushMappedMemoryRanges>(
-                    device.flush_mapped_memory_ranges,
-                ),
-                vkInvalidateMappedMemoryRanges: mem::transmute::<
-                    _,
-                    ffi::PFN_vkInvalidateMappedMemoryRanges,
-                >(
-                    device.invalidate_mapped_memory_ranges
-                ),
-                vkBindBufferMemory: mem::transmute::<_, ffi::PFN_vkBindBufferMemory>(
-                    device.bind_buffer_memory,
+/// Calculated statistics of memory usage e.g. in a specific memory type, heap or custom pool.
+///
+/// These are fast to calculate. See function `Allocator::get_pool_stats`.
+#[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Statistics {
+    /// Number of `erupt::vk::DeviceMemory` objects - Vulkan memory blocks allocated.
+    pub block_count: u32,
+
+    /// Number of `Allocation` objects allocated.
+    ///
+    /// Dedicated allocations have their own blocks, so each one adds 1 to `allocation_count` as well as `block_count`.
+    pub allocation_count: u32,
+
+    /// Number of bytes allocated in `erupt::vk::DeviceMemory` blocks.
+    pub block_bytes: erupt::vk::DeviceSize,
+
+    /// Total number of bytes occupied by all `Allocation` objects.
+    ///
+    /// Always less or equal than `block_bytes`.
+    pub allocation_bytes: erupt::vk::DeviceSize,
+}
+
+/// Statistics of current memory usage and available budget of a memory heap, returned by
+/// `Allocator::get_heap_budgets`.
+///
+/// These are fast to calculate.
+#[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Budget {
+    /// Statistics fetched from the library.
+    pub statistics: Statistics,
+
+    /// Estimated current memory usage of the program, in bytes.
+    ///
+    /// Fetched from the system using `VK_EXT_memory_budget` if enabled. It might be different
+    /// than `statistics.block_bytes` (usually higher) due to additional implicit objects also
+    /// occupying the memory, like swapchain, pipelines, descriptor heaps, command buffers, or
+    /// `erupt::vk::DeviceMemory` blocks allocated outside of this library, if any.
+    pub usage: erupt::vk::DeviceSize,
+
+    /// Estimated amount of memory available to the program, in bytes.
+    ///
+    /// Fetched from the system using `VK_EXT_memory_budget` if enabled. It might be different
+    /// (most probably smaller) than the size of the heap due to factors external to the program,
+    /// decided by the operating system. Difference `budget - usage` is the amount of additional
+    /// memory that can probably be allocated without problems.
+    pub budget: erupt::vk::DeviceSize,
+}
+
+/// More detailed statistics than `Statistics`, calculated by `Allocator::calculate_statistics`.
+///
+/// These are slower to calculate. Use for debugging purposes.
+#[derive(Debug, Default, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DetailedStatistics {
+    /// Number of `erupt::vk::DeviceMemory` objects - Vulkan memory blocks allocated.
+    pub block_count: u32,
+
+    /// Number of `Allocation` objects allocated.
+    ///
+    /// Dedicated allocations have their own blocks, so each one adds 1 to `allocation_count` as well as `block_count`.
+    pub allocation_count: u32,
+
+    /// Number of bytes allocated in `erupt::vk::DeviceMemory` blocks.
+    pub block_bytes: erupt::vk::DeviceSize,
+
+    /// Total number of bytes occupied by all `Allocation` objects.
+    ///
+    /// Always less or equal than `block_bytes`.
+    /// Difference `block_bytes - allocation_bytes` is the amount of memory allocated from Vulkan
+    /// but unused by any `Allocation`.
+    pub allocation_bytes: erupt::vk::DeviceSize,
+
+    /// Number of free ranges of memory between allocations.
+    pub unused_range_count: u32,
+
+    /// Smallest allocation size. `None` if there are 0 allocations.
+    pub allocation_size_min: Option<erupt::vk::DeviceSize>,
+
+    /// Largest allocation size. 0 if there are 0 allocations.
+    pub allocation_size_max: erupt::vk::DeviceSize,
+
+    /// Smallest empty range size. `None` if there are 0 empty ranges.
+    pub unused_range_size_min: Option<erupt::vk::DeviceSize>,
+
+    /// Largest empty range size. 0 if there are 0 empty ranges.
+    pub unused_range_size_max: erupt::vk::DeviceSize,
+}
+
+/// General statistics from current state of the `Allocator` -
+/// total memory usage across all memory heaps and types.
+///
+/// These are slower to calculate. Use for debugging purposes.
+/// See function `Allocator::calculate_statistics`.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TotalStatistics {
+    /// Statistics of each memory type, indexed by memory type index.
+    pub memory_type: Vec<DetailedStatistics>,
+
+    /// Statistics of each memory heap, indexed by memory heap index.
+    pub memory_heap: Vec<DetailedStatistics>,
+
+    /// Statistics summed over all memory types and heaps.
+    pub total: DetailedStatistics,
+}
+
+/// Amount of detail in statistics built by `Allocator::build_stats_string_with`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatsDetail {
+    /// Statistics of memory heaps and types only.
+    #[default]
+    Summary,
+
+    /// Statistics plus a detailed map of all memory blocks and the allocations inside them.
+    Full,
+}
+
+/// Range of an allocation flushed or invalidated by `Allocator::write_bytes_with` and
+/// `Allocator::read_bytes_with`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlushRange {
+    /// Only the bytes that were written or read.
+    #[default]
+    Accessed,
+
+    /// The whole allocation, passing `erupt::vk::WHOLE_SIZE` to the library.
+    ///
+    /// This saves computing the exact range, but may flush or invalidate more memory than
+    /// necessary, which is slower for large allocations in non-`HOST_COHERENT` memory.
+    Whole,
+}
+
+impl FlushRange {
+    /// Returns the offset and size to pass to the library for an access of `len` bytes at `offset`.
+    fn resolve(
+        self,
+        offset: erupt::vk::DeviceSize,
+        len: usize,
+    ) -> (erupt::vk::DeviceSize, erupt::vk::DeviceSize) {
+        match self {
+            FlushRange::Accessed => (offset, len as erupt::vk::DeviceSize),
+            FlushRange::Whole => (0, erupt::vk::WHOLE_SIZE),
+        }
+    }
+}
+
+/// Returns true if bit `memory_type_index` is set in `memory_type_bits`, e.g. in
+/// `erupt::vk::MemoryRequirements::memory_type_bits`.
+pub fn is_memory_type_allowed(memory_type_bits: u32, memory_type_index: u32) -> bool {
+    memory_type_bits
+        .checked_shr(memory_type_index)
+        .is_some_and(|bits| bits & 1 != 0)
+}
+
+/// Builds a memory type bit mask with the bits of the given memory type indices set.
+///
+/// This is the inverse of `memory_type_indices`. Indices of 32 and above are ignored.
+pub fn memory_type_bits(memory_type_indices: impl IntoIterator<Item = u32>) -> u32 {
+    memory_type_indices
+        .into_iter()
+        .filter_map(|index| 1u32.checked_shl(index))
+        .fold(0, |bits, bit| bits | bit)
+}
+
+/// Iterates over the indices of memory types allowed by `memory_type_bits`, in increasing order.
+pub fn memory_type_indices(memory_type_bits: u32) -> impl Iterator<Item = u32> {
+    (0..u32::BITS).filter(move |&index| is_memory_type_allowed(memory_type_bits, index))
+}
+
+impl Allocator {
+    /// Constructor a new `Allocator` using the provided options.
+    ///
+    /// Returns an error of kind `ErrorKind::Config` if `AllocatorCreateInfo::flags` contains
+    /// `AllocatorCreateFlags::EXTERNALLY_SYNCHRONIZED`, which requires `LocalAllocator::new`.
+    pub fn new(create_info: &AllocatorCreateInfo) -> Result<Self> {
+        if create_info
+            .flags
+            .contains(AllocatorCreateFlags::EXTERNALLY_SYNCHRONIZED)
+        {
+            return Err(Error::config(
+                "EXTERNALLY_SYNCHRONIZED allocators must be created with LocalAllocator::new",
+            ));
+        }
+        Self::with_flags(create_info, create_info.flags)
+    }
+
+    /// Returns true if the crate was built with the `corruption_detection` feature.
+    ///
+    /// Without it, `Allocator::check_corruption` and `Allocator::check_pool_corruption` always
+    /// fail with `erupt::vk::Result::ERROR_FEATURE_NOT_PRESENT`, so tools can skip calling them.
+    /// With it, only memory types that are `HOST_VISIBLE` and `HOST_COHERENT` are checked.
+    pub const fn corruption_detection_enabled() -> bool {
+        cfg!(feature = "corruption_detection")
+    }
+}
+
+impl LocalAllocator {
+    /// Constructor a new `LocalAllocator` using the provided options.
+    ///
+    /// `AllocatorCreateFlags::EXTERNALLY_SYNCHRONIZED` is added to `AllocatorCreateInfo::flags`.
+    pub fn new(create_info: &AllocatorCreateInfo) -> Result<Self> {
+        Self::with_flags(
+            create_info,
+            create_info.flags | AllocatorCreateFlags::EXTERNALLY_SYNCHRONIZED,
+        )
+    }
+}
+
+impl<S> Allocator<S> {
+    /// Creates the internal allocator, using `flags` instead of `AllocatorCreateInfo::flags`.
+    fn with_flags(create_info: &AllocatorCreateInfo, flags: AllocatorCreateFlags) -> Result<Self> {
+        let instance = create_info.instance.clone();
+        let device = create_info.device.clone();
+        if let Some(limits) = &create_info.heap_size_limits {
+            let memory_properties = unsafe {
+                instance.get_physical_device_memory_properties(create_info.physical_device)
+            };
+            if !limits.is_empty() && limits.len() != memory_properties.memory_heap_count as usize {
+                return Err(Error::config(format!(
+                    "heap_size_limits has {} elements, but the physical device has {} memory heaps",
+                    limits.len(),
+                    memory_properties.memory_heap_count
+                )));
+            }
+        }
+        let allocation_callbacks = create_info.allocation_callbacks.map(Box::new);
+        let device_memory_callbacks = create_info.device_memory_callbacks.clone();
+        let ffi_device_memory_callbacks =
+            device_memory_callbacks
+                .as_ref()
+                .map(|cbs| ffi::VmaDeviceMemoryCallbacks {
+                    pfnAllocate: if cbs.allocate.is_some() {
+                        Some(device_memory_allocate_callback)
+                    } else {
+                        None
+                    },
+                    pfnFree: if cbs.free.is_some() {
+                        Some(device_memory_free_callback)
+                    } else {
+                        None
+                    },
+                    pUserData: Arc::as_ptr(cbs) as *mut ::std::os::raw::c_void,
+                });
+        // Only route functions that VMA is allowed to use with the requested Vulkan version and
+        // enabled extensions, as loaders leave the others null and VMA would call them otherwise.
+        let vulkan_1_1 = create_info.vulkan_api_version >= erupt::vk::API_VERSION_1_1;
+        let vulkan_1_3 = create_info.vulkan_api_version >= erupt::vk::API_VERSION_1_3;
+        let routed_functions = unsafe {
+            ffi::VmaVulkanFunctions {
+                vkGetInstanceProcAddr: mem::transmute::<_, ffi::PFN_vkGetInstanceProcAddr>(
+                    instance.get_instance_proc_addr,
                 ),
-                vkBindBufferMemory2KHR: mem::transmute::<_, ffi::PFN_vkBindBufferMemory2KHR>(
-                    device.bind_buffer_memory2,
+                vkGetDeviceProcAddr: mem::transmute::<_, ffi::PFN_vkGetDeviceProcAddr>(
+                    instance.get_device_proc_addr,
+                ),
+                vkGetPhysicalDeviceProperties: mem::transmute::<
+                    _,
+                    ffi::PFN_vkGetPhysicalDeviceProperties,
+                >(
+                    instance.get_physical_device_properties
+                ),
+                vkGetPhysicalDeviceMemoryProperties: mem::transmute::<
+                    _,
+                    ffi::PFN_vkGetPhysicalDeviceMemoryProperties,
+                >(
+                    instance.get_physical_device_memory_properties,
+                ),
+                vkAllocateMemory: mem::transmute::<_, ffi::PFN_vkAllocateMemory>(
+                    device.allocate_memory,
                 ),
+                vkFreeMemory: mem::transmute::<_, ffi::PFN_vkFreeMemory>(device.free_memory),
+                vkMapMemory: mem::transmute::<_, ffi::PFN_vkMapMemory>(device.map_memory),
+                vkUnmapMemory: mem::transmute::<_, ffi::PFN_vkUnmapMemory>(device.unmap_memory),
+                vkFlushMappedMemoryRanges: mem::transmute::<_, ffi::PFN_vkFlushMappedMemoryRanges>(
+                    device.flush_mapped_memory_ranges,
+                ),
+                vkInvalidateMappedMemoryRanges: mem::transmute::<
+                    _,
+                    ffi::PFN_vkInvalidateMappedMemoryRanges,
+                >(
+                    device.invalidate_mapped_memory_ranges
+                ),
+                vkBindBufferMemory: mem::transmute::<_, ffi::PFN_vkBindBufferMemory>(
+                    device.bind_buffer_memory,
+                ),
+                vkBindBufferMemory2KHR: if vulkan_1_1 {
+                    mem::transmute::<_, ffi::PFN_vkBindBufferMemory2KHR>(device.bind_buffer_memory2)
+                } else if flags.contains(AllocatorCreateFlags::KHR_BIND_MEMORY2) {
+                    mem::transmute::<_, ffi::PFN_vkBindBufferMemory2KHR>(
+                        device.bind_buffer_memory2_khr,
+                    )
+                } else {
+                    None
+                },
                 vkBindImageMemory: mem::transmute::<_, ffi::PFN_vkBindImageMemory>(
                     device.bind_image_memory,
                 ),
-                vkBindImageMemory2KHR: mem::transmute::<_, ffi::PFN_vkBindImageMemory2KHR>(
-                    device.bind_image_memory2,
-                ),
+                vkBindImageMemory2KHR: if vulkan_1_1 {
+                    mem::transmute::<_, ffi::PFN_vkBindImageMemory2KHR>(device.bind_image_memory2)
+                } else if flags.contains(AllocatorCreateFlags::KHR_BIND_MEMORY2) {
+                    mem::transmute::<_, ffi::PFN_vkBindImageMemory2KHR>(
+                        device.bind_image_memory2_khr,
+                    )
+                } else {
+                    None
+                },
                 vkGetBufferMemoryRequirements: mem::transmute::<
                     _,
                     ffi::PFN_vkGetBufferMemoryRequirements,
@@ -1040,60 +2026,77 @@ impl Allocator {
                 vkCmdCopyBuffer: mem::transmute::<_, ffi::PFN_vkCmdCopyBuffer>(
                     device.cmd_copy_buffer,
                 ),
-                vkGetBufferMemoryRequirements2KHR: mem::transmute::<
-                    _,
-                    ffi::PFN_vkGetBufferMemoryRequirements2KHR,
-                >(
-                    device.get_buffer_memory_requirements2
-                ),
-                vkGetImageMemoryRequirements2KHR: mem::transmute::<
-                    _,
-                    ffi::PFN_vkGetImageMemoryRequirements2KHR,
-                >(
-                    device.get_image_memory_requirements2
-                ),
-                vkGetPhysicalDeviceMemoryProperties2KHR: mem::transmute::<
-                    _,
-                    ffi::PFN_vkGetPhysicalDeviceMemoryProperties2KHR,
-                >(
-                    instance.get_physical_device_properties2
-                ),
-                vkGetDeviceBufferMemoryRequirements: mem::transmute::<
-                    _,
-                    ffi::PFN_vkGetDeviceBufferMemoryRequirements,
-                >(
-                    device.get_device_buffer_memory_requirements
-                ),
-                vkGetDeviceImageMemoryRequirements: mem::transmute::<
-                    _,
-                    ffi::PFN_vkGetDeviceImageMemoryRequirements,
-                >(
-                    device.get_device_image_memory_requirements
-                ),
+                vkGetBufferMemoryRequirements2KHR: if vulkan_1_1 {
+                    mem::transmute::<_, ffi::PFN_vkGetBufferMemoryRequirements2KHR>(
+                        device.get_buffer_memory_requirements2,
+                    )
+                } else if flags.contains(AllocatorCreateFlags::KHR_DEDICATED_ALLOCATION) {
+                    mem::transmute::<_, ffi::PFN_vkGetBufferMemoryRequirements2KHR>(
+                        device.get_buffer_memory_requirements2_khr,
+                    )
+                } else {
+                    None
+                },
+                vkGetImageMemoryRequirements2KHR: if vulkan_1_1 {
+                    mem::transmute::<_, ffi::PFN_vkGetImageMemoryRequirements2KHR>(
+                        device.get_image_memory_requirements2,
+                    )
+                } else if flags.contains(AllocatorCreateFlags::KHR_DEDICATED_ALLOCATION) {
+                    mem::transmute::<_, ffi::PFN_vkGetImageMemoryRequirements2KHR>(
+                        device.get_image_memory_requirements2_khr,
+                    )
+                } else {
+                    None
+                },
+                vkGetPhysicalDeviceMemoryProperties2KHR: if vulkan_1_1 {
+                    mem::transmute::<_, ffi::PFN_vkGetPhysicalDeviceMemoryProperties2KHR>(
+                        instance.get_physical_device_memory_properties2,
+                    )
+                } else if flags.contains(AllocatorCreateFlags::EXT_MEMORY_BUDGET) {
+                    mem::transmute::<_, ffi::PFN_vkGetPhysicalDeviceMemoryProperties2KHR>(
+                        instance.get_physical_device_memory_properties2_khr,
+                    )
+                } else {
+                    None
+                },
+                vkGetDeviceBufferMemoryRequirements: if vulkan_1_3 {
+                    mem::transmute::<_, ffi::PFN_vkGetDeviceBufferMemoryRequirements>(
+                        device.get_device_buffer_memory_requirements,
+                    )
+                } else {
+                    None
+                },
+                vkGetDeviceImageMemoryRequirements: if vulkan_1_3 {
+                    mem::transmute::<_, ffi::PFN_vkGetDeviceImageMemoryRequirements>(
+                        device.get_device_image_memory_requirements,
+                    )
+                } else {
+                    None
+                },
             }
         };
         let ffi_create_info = ffi::VmaAllocatorCreateInfo {
             physicalDevice: create_info.physical_device.to_raw() as ffi::VkPhysicalDevice,
             device: create_info.device.handle.to_raw() as ffi::VkDevice,
             instance: instance.handle.to_raw() as ffi::VkInstance,
-            flags: create_info.flags.bits(),
+            flags: flags.bits(),
             preferredLargeHeapBlockSize: create_info.preferred_large_heap_block_size as u64,
             pHeapSizeLimit: match &create_info.heap_size_limits {
-                None => ::std::ptr::null(),
-                Some(limits) => limits.as_ptr(),
+                Some(limits) if !limits.is_empty() => limits.as_ptr(),
+                _ => ::std::ptr::null(),
             },
             pVulkanFunctions: &routed_functions,
             // Safety: identical structs with repr(C) layouts
-            pAllocationCallbacks: if let Some(cbs) = create_info.allocation_callbacks.as_ref() {
-                unsafe { std::mem::transmute(cbs as *const _) }
-            } else {
-                std::ptr::null()
+            pAllocationCallbacks: match &allocation_callbacks {
+                Some(cbs) => {
+                    &**cbs as *const erupt::vk::AllocationCallbacks
+                        as *const ffi::VkAllocationCallbacks
+                }
+                None => std::ptr::null(),
             },
-            pDeviceMemoryCallbacks: if let Some(cbs) = create_info.device_memory_callbacks.as_ref()
-            {
-                cbs as *const _
-            } else {
-                std::ptr::null()
+            pDeviceMemoryCallbacks: match &ffi_device_memory_callbacks {
+                Some(cbs) => cbs as *const _,
+                None => std::ptr::null(),
             },
             vulkanApiVersion: create_info.vulkan_api_version,
             pTypeExternalMemoryHandleTypes: ::std::ptr::null(), // TODO: Make configurable
@@ -1106,54 +2109,218 @@ impl Allocator {
             )
         });
         match result {
-            erupt::vk::Result::SUCCESS => Ok(Allocator {
+            erupt::vk::Result::SUCCESS => {
+                let (physical_device_properties, memory_properties) =
+                    unsafe { device_properties_of(internal) };
+                Ok(Allocator {
+                    inner: Arc::new(AllocatorInner {
+                        internal,
+                        instance,
+                        device,
+                        allocation_callbacks,
+                        device_memory_callbacks,
+                        pools: Mutex::new(HashMap::new()),
+                        physical_device_properties,
+                        memory_properties,
+                        create_flags: flags,
+                        vulkan_api_version: create_info.vulkan_api_version,
+                        #[cfg(feature = "pool_tracking")]
+                        pool_allocations: Mutex::new(HashMap::new()),
+                        owned: true,
+                    }),
+                    _sync: PhantomData,
+                })
+            }
+            _ => Err(Error::vulkan(result)),
+        }
+    }
+
+    /// Wraps an existing `VmaAllocator` handle, e.g. one created by C++ code, without taking
+    /// ownership of it.
+    ///
+    /// The handle is never destroyed by the returned `Allocator`: dropping it, or calling
+    /// `Allocator::destroy`, only detaches it from the handle. VMA doesn't expose the flags and
+    /// Vulkan API version the handle was created with, so they must be passed as `create_flags`
+    /// and `vulkan_api_version`. They are returned by `Allocator::create_flags` and
+    /// `Allocator::vulkan_api_version` and decide which features this crate uses, e.g. memory
+    /// priorities or buffer device addresses.
+    ///
+    /// # Safety
+    ///
+    /// `internal` must be a valid allocator created for `device` and `instance`, and must outlive
+    /// the returned `Allocator` and all of its clones. Use `LocalAllocator` if it was created with
+    /// `AllocatorCreateFlags::EXTERNALLY_SYNCHRONIZED`. `create_flags` and `vulkan_api_version`
+    /// must be the ones `internal` was created with.
+    pub unsafe fn from_raw(
+        internal: ffi::VmaAllocator,
+        device: Arc<erupt::DeviceLoader>,
+        instance: Arc<erupt::InstanceLoader>,
+        create_flags: AllocatorCreateFlags,
+        vulkan_api_version: u32,
+    ) -> Self {
+        let (physical_device_properties, memory_properties) = device_properties_of(internal);
+        Allocator {
+            inner: Arc::new(AllocatorInner {
                 internal,
                 instance,
                 device,
+                allocation_callbacks: None,
+                device_memory_callbacks: None,
+                pools: Mutex::new(HashMap::new()),
+                physical_device_properties,
+                memory_properties,
+                create_flags,
+                vulkan_api_version,
+                #[cfg(feature = "pool_tracking")]
+                pool_allocations: Mutex::new(HashMap::new()),
+                owned: false,
             }),
-            _ => Err(Error::vulkan(result)),
+            _sync: PhantomData,
         }
     }
 
+    /// Returns the internal `VmaAllocator` handle.
+    ///
+    /// The handle stays owned by this `Allocator` and must not be destroyed through it.
+    /// See `Allocator::from_raw` for the opposite direction.
+    pub fn as_raw(&self) -> ffi::VmaAllocator {
+        self.inner.internal
+    }
+
+    /// Returns information about existing `Allocator` object - handle to Vulkan device etc.
+    ///
+    /// It might be useful if you want to keep just the `Allocator` handle and fetch other required handles to
+    /// `erupt::vk::PhysicalDevice`, `erupt::vk::Device` etc. every time using this function.
+    ///
+    /// Returns an error of kind `ErrorKind::Destroyed` if the allocator has been destroyed.
+    pub fn get_allocator_info(&self) -> Result<AllocatorInfo> {
+        self.check_alive()?;
+        let mut ffi_info: ffi::VmaAllocatorInfo = Default::default();
+        unsafe {
+            ffi::vmaGetAllocatorInfo(self.inner.internal, &mut ffi_info);
+        }
+        let info = AllocatorInfo {
+            instance: erupt::vk::Instance::from_raw(ffi_info.instance as u64),
+            physical_device: erupt::vk::PhysicalDevice::from_raw(ffi_info.physicalDevice as u64),
+            device: erupt::vk::Device::from_raw(ffi_info.device as u64),
+        };
+        debug_assert_eq!(info.instance, self.inner.instance.handle);
+        debug_assert_eq!(info.device, self.inner.device.handle);
+        Ok(info)
+    }
+
     /// The allocator fetches `erupt::vk::PhysicalDeviceProperties` from the physical device.
     /// You can get it here, without fetching it again on your own.
     pub fn get_physical_device_properties(&self) -> Result<erupt::vk::PhysicalDeviceProperties> {
-        let mut ffi_properties: *const ffi::VkPhysicalDeviceProperties = std::ptr::null();
-        Ok(unsafe {
-            ffi::vmaGetPhysicalDeviceProperties(self.internal, &mut ffi_properties);
-            mem::transmute::<ffi::VkPhysicalDeviceProperties, erupt::vk::PhysicalDeviceProperties>(
-                *ffi_properties,
-            )
-        })
+        Ok(self.inner.physical_device_properties)
+    }
+
+    /// Returns the flags the allocator was created with, including
+    /// `AllocatorCreateFlags::EXTERNALLY_SYNCHRONIZED` for a `LocalAllocator`.
+    ///
+    /// Useful to decide at runtime whether features that depend on them are available, e.g.
+    /// whether budgets from `Allocator::get_heap_budgets` are accurate.
+    pub fn create_flags(&self) -> AllocatorCreateFlags {
+        self.inner.create_flags
+    }
+
+    /// Returns `AllocatorCreateInfo::vulkan_api_version` the allocator was created with.
+    ///
+    /// 0 means the allocator assumes Vulkan 1.0.
+    pub fn vulkan_api_version(&self) -> u32 {
+        self.inner.vulkan_api_version
+    }
+
+    /// Returns `erupt::vk::PhysicalDeviceLimits::buffer_image_granularity` of the physical device.
+    ///
+    /// Linear and optimal resources placed closer than this in the same memory block may alias,
+    /// which the library avoids unless a pool is created with
+    /// `AllocatorPoolCreateFlags::IGNORE_BUFFER_IMAGE_GRANULARITY`.
+    pub fn buffer_image_granularity(&self) -> erupt::vk::DeviceSize {
+        self.inner
+            .physical_device_properties
+            .limits
+            .buffer_image_granularity
+    }
+
+    /// Returns `erupt::vk::PhysicalDeviceLimits::non_coherent_atom_size` of the physical device.
+    ///
+    /// Ranges flushed or invalidated in memory that isn't `HOST_COHERENT` are rounded to
+    /// multiples of this size.
+    pub fn non_coherent_atom_size(&self) -> erupt::vk::DeviceSize {
+        self.inner
+            .physical_device_properties
+            .limits
+            .non_coherent_atom_size
     }
 
     /// The allocator fetches `erupt::vk::PhysicalDeviceMemoryProperties` from the physical device.
     /// You can get it here, without fetching it again on your own.
     pub fn get_memory_properties(&self) -> Result<erupt::vk::PhysicalDeviceMemoryProperties> {
-        let mut ffi_properties: *const ffi::VkPhysicalDeviceMemoryProperties = std::ptr::null();
-        Ok(unsafe {
-            ffi::vmaGetMemoryProperties(self.internal, &mut ffi_properties);
-            mem::transmute::<
-                ffi::VkPhysicalDeviceMemoryProperties,
-                erupt::vk::PhysicalDeviceMemoryProperties,
-            >(*ffi_properties)
-        })
+        Ok(self.inner.memory_properties)
+    }
+
+    /// Returns the number of memory types of the physical device.
+    ///
+    /// Valid memory type indices are less than this value.
+    pub fn memory_type_count(&self) -> u32 {
+        self.inner.memory_properties.memory_type_count
+    }
+
+    /// Returns the number of memory heaps of the physical device.
+    ///
+    /// Valid memory heap indices are less than this value.
+    pub fn memory_heap_count(&self) -> u32 {
+        self.inner.memory_properties.memory_heap_count
     }
 
     /// Given a memory type index, returns `erupt::vk::MemoryPropertyFlags` of this memory type.
     ///
     /// This is just a convenience function; the same information can be obtained using
     /// `Allocator::get_memory_properties`.
+    ///
+    /// Returns an error of kind `ErrorKind::Config` if `memory_type_index` isn't less than
+    /// `Allocator::memory_type_count`.
     pub fn get_memory_type_properties(
         &self,
         memory_type_index: u32,
     ) -> Result<erupt::vk::MemoryPropertyFlags> {
-        let mut ffi_properties: ffi::VkMemoryPropertyFlags = Default::default();
-        Ok(unsafe {
-            ffi::vmaGetMemoryTypeProperties(self.internal, memory_type_index, &mut ffi_properties);
-            mem::transmute::<ffi::VkMemoryPropertyFlags, erupt::vk::MemoryPropertyFlags>(
-                ffi_properties,
-            )
+        let memory_type_count = self.memory_type_count();
+        if memory_type_index >= memory_type_count {
+            return Err(Error::config(format!(
+                "memory type index {} is out of range, the device has {} memory types",
+                memory_type_index, memory_type_count
+            )));
+        }
+        Ok(self.inner.memory_properties.memory_types[memory_type_index as usize].property_flags)
+    }
+
+    /// Returns the index of the memory heap that memory type `memory_type_index` belongs to.
+    ///
+    /// Useful for aggregating per memory type statistics, e.g. `TotalStatistics::memory_type`,
+    /// into heaps.
+    ///
+    /// Returns `None` if `memory_type_index` isn't less than `Allocator::memory_type_count`.
+    pub fn memory_type_heap_index(&self, memory_type_index: u32) -> Option<u32> {
+        if memory_type_index >= self.memory_type_count() {
+            return None;
+        }
+        Some(self.inner.memory_properties.memory_types[memory_type_index as usize].heap_index)
+    }
+
+    /// Returns the indices of all memory types whose property flags contain all of `required`.
+    ///
+    /// The indices are yielded in increasing order. Combine with `Allocator::find_memory_type_index`
+    /// or pass them to `AllocatorPoolCreateInfo::memory_type_index` to pick a memory type by hand.
+    pub fn memory_types_with(
+        &self,
+        required: erupt::vk::MemoryPropertyFlags,
+    ) -> impl Iterator<Item = u32> {
+        let memory_properties = self.inner.memory_properties;
+        (0..memory_properties.memory_type_count).filter(move |&index| {
+            memory_properties.memory_types[index as usize]
+                .property_flags
+                .contains(required)
         })
     }
 
@@ -1164,28 +2331,144 @@ impl Allocator {
     /// Allocations queried using `Allocator::get_allocation_info` cannot become lost
     /// in the current frame.
     pub fn set_current_frame_index(&self, frame_index: u32) {
+        if self.is_destroyed() {
+            return;
+        }
         unsafe {
-            ffi::vmaSetCurrentFrameIndex(self.internal, frame_index);
+            ffi::vmaSetCurrentFrameIndex(self.inner.internal, frame_index);
         }
     }
 
     /// Retrieves statistics from current state of the `Allocator`.
-    pub fn calculate_statistics(&self) -> Result<ffi::VmaTotalStatistics> {
+    ///
+    /// This function is called "calculate" not "get" because it has to traverse all
+    /// internal data structures, so it may be quite slow. Use it for debugging purposes.
+    ///
+    /// `TotalStatistics::memory_type` and `TotalStatistics::memory_heap` contain one entry
+    /// per memory type and memory heap of the physical device respectively.
+    pub fn calculate_statistics(&self) -> Result<TotalStatistics> {
+        let mut stats = TotalStatistics::default();
+        self.calculate_statistics_into(&mut stats)?;
+        Ok(stats)
+    }
+
+    /// Takes a snapshot of the current statistics, e.g. to be sent to a logging or telemetry
+    /// thread. `TotalStatistics` is plain data, so it is `Send` and `Sync`.
+    ///
+    /// Same as `Allocator::calculate_statistics`, and just as slow.
+    pub fn snapshot(&self) -> Result<TotalStatistics> {
+        self.calculate_statistics()
+    }
+
+    /// Same as `Allocator::calculate_statistics`, but stores the result in `out`, reusing its
+    /// vectors so that calling it repeatedly, e.g. every frame, doesn't allocate.
+    pub fn calculate_statistics_into(&self, out: &mut TotalStatistics) -> Result<()> {
+        self.check_alive()?;
         let mut vma_stats: ffi::VmaTotalStatistics = Default::default();
         unsafe {
-            ffi::vmaCalculateStatistics(self.internal, &mut vma_stats as *mut _);
+            ffi::vmaCalculateStatistics(self.inner.internal, &mut vma_stats as *mut _);
+        }
+        out.memory_type.clear();
+        out.memory_type.extend(
+            vma_stats.memoryType[..self.memory_type_count() as usize]
+                .iter()
+                .map(detailed_statistics_from_ffi),
+        );
+        out.memory_heap.clear();
+        out.memory_heap.extend(
+            vma_stats.memoryHeap[..self.memory_heap_count() as usize]
+                .iter()
+                .map(detailed_statistics_from_ffi),
+        );
+        out.total = detailed_statistics_from_ffi(&vma_stats.total);
+        Ok(())
+    }
+
+    /// Retrieves information about current memory usage and budget of every memory heap,
+    /// indexed by memory heap index.
+    ///
+    /// This function is called "get" not "calculate" because it is very fast, suitable to be
+    /// called every frame or every allocation.
+    ///
+    /// Usage and budget are accurate only if the allocator was created with
+    /// `AllocatorCreateFlags::EXT_MEMORY_BUDGET`. Otherwise they are estimated from the
+    /// statistics of the library and the heap sizes.
+    pub fn get_heap_budgets(&self) -> Result<Vec<Budget>> {
+        self.check_alive()?;
+        let mut vma_budgets = [ffi::VmaBudget::default(); erupt::vk::MAX_MEMORY_HEAPS as usize];
+        unsafe {
+            ffi::vmaGetHeapBudgets(self.inner.internal, vma_budgets.as_mut_ptr());
         }
-        Ok(vma_stats)
+        Ok(vma_budgets[..self.memory_heap_count() as usize]
+            .iter()
+            .map(budget_from_ffi)
+            .collect())
+    }
+
+    /// Returns the current budget of the single memory heap `heap_index`.
+    ///
+    /// Same as indexing the result of `Allocator::get_heap_budgets`, without allocating a vector
+    /// for all heaps. Returns an error of kind `ErrorKind::Config` if `heap_index` is not less than
+    /// `erupt::vk::PhysicalDeviceMemoryProperties::memory_heap_count`.
+    pub fn heap_budget(&self, heap_index: u32) -> Result<Budget> {
+        self.check_alive()?;
+        let memory_heap_count = self.memory_heap_count();
+        if heap_index >= memory_heap_count {
+            return Err(Error::config(format!(
+                "memory heap index {} is out of range, the device has {} memory heaps",
+                heap_index, memory_heap_count
+            )));
+        }
+        let mut vma_budgets = [ffi::VmaBudget::default(); erupt::vk::MAX_MEMORY_HEAPS as usize];
+        unsafe {
+            ffi::vmaGetHeapBudgets(self.inner.internal, vma_budgets.as_mut_ptr());
+        }
+        Ok(budget_from_ffi(&vma_budgets[heap_index as usize]))
+    }
+
+    /// Returns `usage / budget` of every memory heap, clamped to `[0, 1]`, e.g. for displaying
+    /// memory pressure in a HUD. Heaps with a budget of 0 report 0.
+    ///
+    /// The values come from `Allocator::get_heap_budgets`, so they reflect memory used by other
+    /// objects and processes only if `AllocatorCreateFlags::EXT_MEMORY_BUDGET` was used.
+    pub fn memory_pressure(&self) -> Result<Vec<f32>> {
+        Ok(self
+            .get_heap_budgets()?
+            .iter()
+            .map(|budget| {
+                if budget.budget == 0 {
+                    0.0
+                } else {
+                    (budget.usage as f64 / budget.budget as f64).clamp(0.0, 1.0) as f32
+                }
+            })
+            .collect())
     }
 
     /// Builds and returns statistics in `JSON` format.
+    ///
+    /// Same as `Allocator::build_stats_string_with` with `StatsDetail::Full` if `detailed_map`
+    /// is true, and `StatsDetail::Summary` otherwise.
     pub fn build_stats_string(&self, detailed_map: bool) -> Result<String> {
+        self.build_stats_string_with(if detailed_map {
+            StatsDetail::Full
+        } else {
+            StatsDetail::Summary
+        })
+    }
+
+    /// Builds and returns statistics in `JSON` format, with the amount of detail given by `detail`.
+    pub fn build_stats_string_with(&self, detail: StatsDetail) -> Result<String> {
+        self.check_alive()?;
         let mut stats_string: *mut ::std::os::raw::c_char = ::std::ptr::null_mut();
         unsafe {
             ffi::vmaBuildStatsString(
-                self.internal,
+                self.inner.internal,
                 &mut stats_string,
-                if detailed_map { 1 } else { 0 },
+                match detail {
+                    StatsDetail::Summary => erupt::vk::FALSE,
+                    StatsDetail::Full => erupt::vk::TRUE,
+                },
             );
         }
         Ok(if stats_string.is_null() {
@@ -1197,7 +2480,7 @@ impl Allocator {
                     .into_owned()
             };
             unsafe {
-                ffi::vmaFreeStatsString(self.internal, stats_string);
+                ffi::vmaFreeStatsString(self.inner.internal, stats_string);
             }
             result
         })
@@ -1222,11 +2505,12 @@ impl Allocator {
         memory_type_bits: u32,
         allocation_info: &AllocationCreateInfo,
     ) -> Result<u32> {
+        self.check_alive()?;
         let create_info = allocation_create_info_to_ffi(allocation_info);
         let mut memory_type_index: u32 = 0;
         let result = ffi_to_result(unsafe {
             ffi::vmaFindMemoryTypeIndex(
-                self.internal,
+                self.inner.internal,
                 memory_type_bits,
                 &create_info,
                 &mut memory_type_index,
@@ -1253,14 +2537,13 @@ impl Allocator {
         buffer_info: &erupt::vk::BufferCreateInfo,
         allocation_info: &AllocationCreateInfo,
     ) -> Result<u32> {
+        self.check_alive()?;
         let allocation_create_info = allocation_create_info_to_ffi(allocation_info);
-        let buffer_create_info = unsafe {
-            mem::transmute::<erupt::vk::BufferCreateInfo, ffi::VkBufferCreateInfo>(*buffer_info)
-        };
+        let buffer_create_info = buffer_create_info_to_ffi(buffer_info);
         let mut memory_type_index: u32 = 0;
         let result = ffi_to_result(unsafe {
             ffi::vmaFindMemoryTypeIndexForBufferInfo(
-                self.internal,
+                self.inner.internal,
                 &buffer_create_info,
                 &allocation_create_info,
                 &mut memory_type_index,
@@ -1287,14 +2570,14 @@ impl Allocator {
         image_info: &erupt::vk::ImageCreateInfo,
         allocation_info: &AllocationCreateInfo,
     ) -> Result<u32> {
+        self.check_alive()?;
+        validate_image_create_info(image_info)?;
         let allocation_create_info = allocation_create_info_to_ffi(allocation_info);
-        let image_create_info = unsafe {
-            mem::transmute::<erupt::vk::ImageCreateInfo, ffi::VkImageCreateInfo>(*image_info)
-        };
+        let image_create_info = image_create_info_to_ffi(image_info);
         let mut memory_type_index: u32 = 0;
         let result = ffi_to_result(unsafe {
             ffi::vmaFindMemoryTypeIndexForImageInfo(
-                self.internal,
+                self.inner.internal,
                 &image_create_info,
                 &allocation_create_info,
                 &mut memory_type_index,
@@ -1306,33 +2589,367 @@ impl Allocator {
         }
     }
 
+    /// Same as `Allocator::find_memory_type_index`, but returns `Ok(None)` instead of an error
+    /// if no memory type matches, so that only actual failures are reported as errors.
+    pub fn try_find_memory_type_index(
+        &self,
+        memory_type_bits: u32,
+        allocation_info: &AllocationCreateInfo,
+    ) -> Result<Option<u32>> {
+        memory_type_index_if_found(self.find_memory_type_index(memory_type_bits, allocation_info))
+    }
+
+    /// Same as `Allocator::find_memory_type_index_for_buffer_info`, but returns `Ok(None)`
+    /// instead of an error if no memory type matches.
+    pub fn try_find_memory_type_index_for_buffer_info(
+        &self,
+        buffer_info: &erupt::vk::BufferCreateInfo,
+        allocation_info: &AllocationCreateInfo,
+    ) -> Result<Option<u32>> {
+        memory_type_index_if_found(
+            self.find_memory_type_index_for_buffer_info(buffer_info, allocation_info),
+        )
+    }
+
+    /// Same as `Allocator::find_memory_type_index_for_image_info`, but returns `Ok(None)`
+    /// instead of an error if no memory type matches.
+    pub fn try_find_memory_type_index_for_image_info(
+        &self,
+        image_info: &erupt::vk::ImageCreateInfo,
+        allocation_info: &AllocationCreateInfo,
+    ) -> Result<Option<u32>> {
+        memory_type_index_if_found(
+            self.find_memory_type_index_for_image_info(image_info, allocation_info),
+        )
+    }
+
+    /// Finds a memory type index for 2D images with the given `format`, `usage` and `tiling`,
+    /// e.g. to choose `AllocatorPoolCreateInfo::memory_type_index` of a pool of textures.
+    ///
+    /// Same as `Allocator::find_memory_type_index_for_image_info` with a minimal image of
+    /// 1x1 texel, a single mip level and array layer, one sample and exclusive sharing. Images
+    /// with other parameters may have different memory requirements on some implementations;
+    /// use `Allocator::find_memory_type_index_for_image_info` with the full description for those.
+    pub fn memory_type_for_image_usage(
+        &self,
+        format: erupt::vk::Format,
+        usage: erupt::vk::ImageUsageFlags,
+        tiling: erupt::vk::ImageTiling,
+        allocation_info: &AllocationCreateInfo,
+    ) -> Result<u32> {
+        let image_info = erupt::vk::ImageCreateInfoBuilder::new()
+            .image_type(erupt::vk::ImageType::_2D)
+            .format(format)
+            .extent(erupt::vk::Extent3D {
+                width: 1,
+                height: 1,
+                depth: 1,
+            })
+            .mip_levels(1)
+            .array_layers(1)
+            .samples(erupt::vk::SampleCountFlagBits::_1)
+            .tiling(tiling)
+            .usage(usage)
+            .sharing_mode(erupt::vk::SharingMode::EXCLUSIVE)
+            .initial_layout(erupt::vk::ImageLayout::UNDEFINED);
+        self.find_memory_type_index_for_image_info(&image_info, allocation_info)
+    }
+
+    /// Returns true if the driver requires or prefers a buffer described by `buffer_info` to have
+    /// its own dedicated allocation, in which case `Allocator::create_buffer` creates one when
+    /// the allocator was created with `AllocatorCreateFlags::KHR_DEDICATED_ALLOCATION` or
+    /// Vulkan 1.1.
+    ///
+    /// A temporary buffer is created to query `erupt::vk::MemoryDedicatedRequirements`, and is
+    /// destroyed before returning. VMA may still decide to use a dedicated allocation for other
+    /// reasons, e.g. when the buffer is bigger than half of the preferred block size.
+    ///
+    /// Returns an error of kind `ErrorKind::Unsupported` if neither Vulkan 1.1 nor
+    /// `VK_KHR_get_memory_requirements2` is enabled on the device.
+    pub fn would_use_dedicated_for_buffer(
+        &self,
+        buffer_info: &erupt::vk::BufferCreateInfo,
+    ) -> Result<bool> {
+        self.check_alive()?;
+        let device = &self.inner.device;
+        let get_memory_requirements2 = device
+            .get_buffer_memory_requirements2
+            .or(device.get_buffer_memory_requirements2_khr)
+            .ok_or_else(|| Error::unsupported(
+                    "querying dedicated allocation requirements needs Vulkan 1.1 or VK_KHR_get_memory_requirements2",
+                ))?;
+        let allocation_callbacks = self.inner.allocation_callbacks.as_deref();
+        let buffer = unsafe { device.create_buffer(buffer_info, allocation_callbacks) }
+            .result()
+            .map_err(Error::vulkan)?;
+        let info = erupt::vk::BufferMemoryRequirementsInfo2Builder::new().buffer(buffer);
+        let mut dedicated = *erupt::vk::MemoryDedicatedRequirementsBuilder::new();
+        let mut requirements = *erupt::vk::MemoryRequirements2Builder::new();
+        requirements.p_next = &mut dedicated as *mut _ as *mut ::std::os::raw::c_void;
+        unsafe {
+            get_memory_requirements2(device.handle, &*info, &mut requirements);
+            device.destroy_buffer(buffer, allocation_callbacks);
+        }
+        Ok(dedicated.requires_dedicated_allocation == erupt::vk::TRUE
+            || dedicated.prefers_dedicated_allocation == erupt::vk::TRUE)
+    }
+
+    /// Same as `Allocator::would_use_dedicated_for_buffer`, but for an image described by
+    /// `image_info`.
+    pub fn would_use_dedicated_for_image(
+        &self,
+        image_info: &erupt::vk::ImageCreateInfo,
+    ) -> Result<bool> {
+        self.check_alive()?;
+        validate_image_create_info(image_info)?;
+        let device = &self.inner.device;
+        let get_memory_requirements2 = device
+            .get_image_memory_requirements2
+            .or(device.get_image_memory_requirements2_khr)
+            .ok_or_else(|| Error::unsupported(
+                    "querying dedicated allocation requirements needs Vulkan 1.1 or VK_KHR_get_memory_requirements2",
+                ))?;
+        let allocation_callbacks = self.inner.allocation_callbacks.as_deref();
+        let image = unsafe { device.create_image(image_info, allocation_callbacks) }
+            .result()
+            .map_err(Error::vulkan)?;
+        let info = erupt::vk::ImageMemoryRequirementsInfo2Builder::new().image(image);
+        let mut dedicated = *erupt::vk::MemoryDedicatedRequirementsBuilder::new();
+        let mut requirements = *erupt::vk::MemoryRequirements2Builder::new();
+        requirements.p_next = &mut dedicated as *mut _ as *mut ::std::os::raw::c_void;
+        unsafe {
+            get_memory_requirements2(device.handle, &*info, &mut requirements);
+            device.destroy_image(image, allocation_callbacks);
+        }
+        Ok(dedicated.requires_dedicated_allocation == erupt::vk::TRUE
+            || dedicated.prefers_dedicated_allocation == erupt::vk::TRUE)
+    }
+
     /// Allocates Vulkan device memory and creates `AllocatorPool` object.
     pub fn create_pool(&self, pool_info: &AllocatorPoolCreateInfo) -> Result<AllocatorPool> {
+        self.check_alive()?;
+        self.check_priority(pool_info.priority)?;
         let mut ffi_pool: ffi::VmaPool = std::ptr::null_mut();
         let create_info = pool_create_info_to_ffi(pool_info);
         let result = ffi_to_result(unsafe {
-            ffi::vmaCreatePool(self.internal, &create_info, &mut ffi_pool)
+            ffi::vmaCreatePool(self.inner.internal, &create_info, &mut ffi_pool)
         });
         match result {
-            erupt::vk::Result::SUCCESS => Ok(AllocatorPool { internal: ffi_pool }),
+            erupt::vk::Result::SUCCESS => {
+                let pool = AllocatorPool { internal: ffi_pool };
+                self.inner
+                    .pools
+                    .lock()
+                    .unwrap()
+                    .insert(pool.clone(), pool_info.clone());
+                Ok(pool)
+            }
             _ => Err(Error::vulkan(result)),
         }
     }
 
     /// Destroys `AllocatorPool` object and frees Vulkan device memory.
     pub fn destroy_pool(&self, pool: &AllocatorPool) {
+        if self.is_destroyed() {
+            return;
+        }
+        self.inner.pools.lock().unwrap().remove(pool);
+        #[cfg(feature = "pool_tracking")]
+        self.inner
+            .pool_allocations
+            .lock()
+            .unwrap()
+            .retain(|_, allocation_pool| allocation_pool != pool);
         unsafe {
-            ffi::vmaDestroyPool(self.internal, pool.internal);
+            ffi::vmaDestroyPool(self.inner.internal, pool.internal);
+        }
+    }
+
+    /// Returns true if an allocation whose memory requirements allow the memory types in
+    /// `memory_type_bits` can be made in `pool`, i.e. if the bit of the pool's memory type is set.
+    ///
+    /// Returns `None` if the memory type of the pool is unknown, in the same cases as
+    /// `Allocator::get_pool_memory_type_index`.
+    pub fn is_pool_compatible(&self, pool: &AllocatorPool, memory_type_bits: u32) -> Option<bool> {
+        self.get_pool_memory_type_index(pool)
+            .map(|memory_type_index| is_memory_type_allowed(memory_type_bits, memory_type_index))
+    }
+
+    /// Destroys `pool` and creates a new one with the same `AllocatorPoolCreateInfo`, e.g. to
+    /// release all memory of a per-level pool at once.
+    ///
+    /// All allocations made in `pool` must already be freed, as with `Allocator::destroy_pool`.
+    /// With the `pool_tracking` feature, `Allocator::reset_pool` can free them first.
+    ///
+    /// Returns an error of kind `ErrorKind::Config` without destroying anything if the pool wasn't
+    /// created with `Allocator::create_pool` on this allocator, or if it was created with
+    /// `AllocatorPoolCreateInfo::memory_allocate_next`, as that chain is only guaranteed to live
+    /// until the old pool is destroyed. If creating the new pool fails, the old one is destroyed
+    /// nevertheless.
+    pub fn recreate_pool(&self, pool: &AllocatorPool) -> Result<AllocatorPool> {
+        self.check_alive()?;
+        let pool_info = self
+            .inner
+            .pools
+            .lock()
+            .unwrap()
+            .get(pool)
+            .cloned()
+            .ok_or_else(|| Error::config("pool wasn't created by this allocator"))?;
+        if pool_info
+            .memory_allocate_next
+            .is_some_and(|next| !next.is_null())
+        {
+            return Err(Error::config(
+                "pools created with memory_allocate_next can't be recreated",
+            ));
+        }
+        self.destroy_pool(pool);
+        self.create_pool(&pool_info)
+    }
+
+    /// Frees all allocations made in `pool` that haven't been freed yet, leaving the pool empty.
+    ///
+    /// Only the memory is freed. Buffers and images bound to it must be destroyed by the caller
+    /// beforehand with `erupt::DeviceLoader::destroy_buffer` or `erupt::DeviceLoader::destroy_image`.
+    /// The allocations are found the same way as in `Allocator::pool_allocations`.
+    #[cfg(feature = "pool_tracking")]
+    pub fn reset_pool(&self, pool: &AllocatorPool) {
+        let allocations = self.pool_allocations(pool);
+        if !allocations.is_empty() {
+            self.free_memory_pages(&allocations);
+        }
+    }
+
+    /// Returns the index of the memory type the given pool allocates from, i.e.
+    /// `AllocatorPoolCreateInfo::memory_type_index` it was created with.
+    ///
+    /// Returns `None` if the pool wasn't created with `Allocator::create_pool` on this allocator,
+    /// e.g. when it was obtained through `AllocatorPool::from_raw`, or if it was already destroyed.
+    pub fn get_pool_memory_type_index(&self, pool: &AllocatorPool) -> Option<u32> {
+        self.inner
+            .pools
+            .lock()
+            .unwrap()
+            .get(pool)
+            .map(|pool_info| pool_info.memory_type_index)
+    }
+
+    /// Returns all allocations made in `pool` that haven't been freed yet, e.g. to report the
+    /// leaked ones before destroying the pool.
+    ///
+    /// Allocations are recorded by every function of this allocator that allocates or frees
+    /// memory, at the cost of a mutex lock and a hash map update each time. This is why it's
+    /// only available with the `pool_tracking` feature. Allocations made by calling VMA directly
+    /// through the raw handles are not seen.
+    #[cfg(feature = "pool_tracking")]
+    pub fn pool_allocations(&self, pool: &AllocatorPool) -> Vec<Allocation> {
+        self.inner
+            .pool_allocations
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, allocation_pool)| *allocation_pool == pool)
+            .map(|(allocation, _)| *allocation)
+            .collect()
+    }
+
+    /// Records allocations made in `pool` for `Allocator::pool_allocations`.
+    #[cfg(feature = "pool_tracking")]
+    fn track_pool_allocations(
+        &self,
+        pool: ffi::VmaPool,
+        allocations: impl IntoIterator<Item = Allocation>,
+    ) {
+        if !pool.is_null() {
+            let mut pool_allocations = self.inner.pool_allocations.lock().unwrap();
+            for allocation in allocations {
+                pool_allocations.insert(allocation, AllocatorPool { internal: pool });
+            }
+        }
+    }
+
+    #[cfg(not(feature = "pool_tracking"))]
+    #[inline(always)]
+    fn track_pool_allocations(
+        &self,
+        _pool: ffi::VmaPool,
+        _allocations: impl IntoIterator<Item = Allocation>,
+    ) {
+    }
+
+    /// Forgets freed allocations recorded by `Allocator::track_pool_allocations`.
+    #[cfg(feature = "pool_tracking")]
+    fn untrack_pool_allocations(&self, allocations: impl IntoIterator<Item = Allocation>) {
+        let mut pool_allocations = self.inner.pool_allocations.lock().unwrap();
+        for allocation in allocations {
+            pool_allocations.remove(&allocation);
+        }
+    }
+
+    #[cfg(not(feature = "pool_tracking"))]
+    #[inline(always)]
+    fn untrack_pool_allocations(&self, _allocations: impl IntoIterator<Item = Allocation>) {}
+
+    /// Logs allocations that were just made with `log::debug!`, with the `log` feature.
+    #[cfg(feature = "log")]
+    fn log_allocated(&self, allocations: impl IntoIterator<Item = Allocation>) {
+        self.log_allocations("allocated", allocations);
+    }
+
+    #[cfg(not(feature = "log"))]
+    #[inline(always)]
+    fn log_allocated(&self, _allocations: impl IntoIterator<Item = Allocation>) {}
+
+    /// Logs allocations that are about to be freed with `log::debug!`, with the `log` feature.
+    #[cfg(feature = "log")]
+    fn log_freeing(&self, allocations: impl IntoIterator<Item = Allocation>) {
+        self.log_allocations("freeing", allocations);
+    }
+
+    #[cfg(not(feature = "log"))]
+    #[inline(always)]
+    fn log_freeing(&self, _allocations: impl IntoIterator<Item = Allocation>) {}
+
+    #[cfg(feature = "log")]
+    fn log_allocations(&self, action: &str, allocations: impl IntoIterator<Item = Allocation>) {
+        if !log::log_enabled!(log::Level::Debug) {
+            return;
+        }
+        for allocation in allocations {
+            // Null allocations are skipped, as there is nothing to report about them.
+            if let Ok(allocation_info) = self.get_allocation_info(&allocation) {
+                log::debug!(
+                    "{} {:?}: {} bytes at offset {} of {:?} in memory type {}, name {:?}",
+                    action,
+                    allocation.internal,
+                    allocation_info.get_size(),
+                    allocation_info.get_offset(),
+                    allocation_info.get_device_memory(),
+                    allocation_info.get_memory_type(),
+                    allocation_info.get_name_str(),
+                );
+            }
         }
     }
 
     /// Retrieves statistics of existing `AllocatorPool` object.
-    pub fn get_pool_stats(&self, pool: &AllocatorPool) -> Result<ffi::VmaStatistics> {
+    pub fn get_pool_stats(&self, pool: &AllocatorPool) -> Result<Statistics> {
+        self.check_alive()?;
         let mut pool_stats: ffi::VmaStatistics = Default::default();
         unsafe {
-            ffi::vmaGetPoolStatistics(self.internal, pool.internal, &mut pool_stats);
+            ffi::vmaGetPoolStatistics(self.inner.internal, pool.internal, &mut pool_stats);
         }
-        Ok(pool_stats)
+        Ok(statistics_from_ffi(&pool_stats))
+    }
+
+    /// Returns true if no allocations are left in `pool`, e.g. to check that everything was freed
+    /// before calling `Allocator::destroy_pool`.
+    ///
+    /// Fails in the same cases as `Allocator::get_pool_stats`, e.g. with an error of kind
+    /// `ErrorKind::Destroyed` if the allocator has already been destroyed.
+    pub fn is_pool_empty(&self, pool: &AllocatorPool) -> Result<bool> {
+        Ok(self.get_pool_stats(pool)?.allocation_count == 0)
     }
 
     /// Checks magic number in margins around all allocations in given memory pool in search for corruptions.
@@ -1340,22 +2957,74 @@ impl Allocator {
     /// Corruption detection is enabled only when `VMA_DEBUG_DETECT_CORRUPTION` macro is defined to nonzero,
     /// `VMA_DEBUG_MARGIN` is defined to nonzero and the pool is created in memory type that is
     /// `erupt::vk::MemoryPropertyFlags::HOST_VISIBLE` and `erupt::vk::MemoryPropertyFlags::HOST_COHERENT`.
+    /// The `corruption_detection` feature of this crate defines both macros.
     ///
     /// Possible error values:
     ///
     /// - `erupt::vk::Result::ERROR_FEATURE_NOT_PRESENT` - corruption detection is not enabled for specified pool.
-    /// - `erupt::vk::Result::ERROR_VALIDATION_FAILED_EXT` - corruption detection has been performed and found memory corruptions around one of the allocations.
-    ///   `VMA_ASSERT` is also fired in that case.
+    /// - `erupt::vk::Result::ERROR_UNKNOWN` - corruption detection has been performed and found memory corruptions around one of the allocations.
+    ///   With the `corruption_detection` feature the `VMA_ASSERT` fired in that case only prints a message to stderr
+    ///   instead of aborting the process.
     /// - Other value: Error returned by Vulkan, e.g. memory mapping failure.
     pub fn check_pool_corruption(&self, pool: &AllocatorPool) -> Result<()> {
-        let result =
-            ffi_to_result(unsafe { ffi::vmaCheckPoolCorruption(self.internal, pool.internal) });
+        self.check_alive()?;
+        let result = ffi_to_result(unsafe {
+            ffi::vmaCheckPoolCorruption(self.inner.internal, pool.internal)
+        });
         match result {
             erupt::vk::Result::SUCCESS => Ok(()),
             _ => Err(Error::vulkan(result)),
         }
     }
 
+    /// Same as `Allocator::allocate_memory`, but takes memory requirements queried with
+    /// `vkGet*MemoryRequirements2` and honors the `erupt::vk::MemoryDedicatedRequirements`
+    /// found in their `p_next` chain.
+    ///
+    /// If the driver requires a dedicated allocation, `AllocationCreateFlags::DEDICATED_MEMORY`
+    /// is added to `allocation_info.flags`. If it only prefers one, the flag is added unless
+    /// `allocation_info` uses a custom pool or `AllocationCreateFlags::NEVER_ALLOCATE`.
+    ///
+    /// The memory is not tied to a particular resource. For resources whose dedicated memory must
+    /// be allocated with `erupt::vk::MemoryDedicatedAllocateInfo` naming them, use
+    /// `Allocator::allocate_memory_for_buffer` or `Allocator::allocate_memory_for_image`.
+    ///
+    /// # Safety
+    ///
+    /// The `p_next` chain of `memory_requirements` must be null or consist of valid Vulkan
+    /// structures, each with a correct `s_type`, e.g. as filled in by the driver.
+    pub unsafe fn allocate_memory2(
+        &self,
+        memory_requirements: &erupt::vk::MemoryRequirements2,
+        allocation_info: &AllocationCreateInfo,
+    ) -> Result<(Allocation, AllocationInfo)> {
+        let dedicated = dedicated_requirements(memory_requirements);
+        let (requires_dedicated, prefers_dedicated) = match dedicated {
+            Some(dedicated) => (
+                dedicated.requires_dedicated_allocation == erupt::vk::TRUE,
+                dedicated.prefers_dedicated_allocation == erupt::vk::TRUE,
+            ),
+            None => (false, false),
+        };
+        let use_dedicated = requires_dedicated
+            || (prefers_dedicated
+                && allocation_info.pool.is_none()
+                && !allocation_info
+                    .flags
+                    .contains(AllocationCreateFlags::NEVER_ALLOCATE));
+        if use_dedicated {
+            self.allocate_memory(
+                &memory_requirements.memory_requirements,
+                &AllocationCreateInfo {
+                    flags: allocation_info.flags | AllocationCreateFlags::DEDICATED_MEMORY,
+                    ..allocation_info.clone()
+                },
+            )
+        } else {
+            self.allocate_memory(&memory_requirements.memory_requirements, allocation_info)
+        }
+    }
+
     /// General purpose memory allocation.
     ///
     /// You should free the memory using `Allocator::free_memory` or 'Allocator::free_memory_pages'.
@@ -1367,17 +3036,19 @@ impl Allocator {
         memory_requirements: &erupt::vk::MemoryRequirements,
         allocation_info: &AllocationCreateInfo,
     ) -> Result<(Allocation, AllocationInfo)> {
+        self.check_alive()?;
         let ffi_requirements = unsafe {
             mem::transmute::<erupt::vk::MemoryRequirements, ffi::VkMemoryRequirements>(
                 *memory_requirements,
             )
         };
+        self.validate_allocation_create_info(allocation_info)?;
         let create_info = allocation_create_info_to_ffi(allocation_info);
         let mut allocation: Allocation = Default::default();
         let mut allocation_info: AllocationInfo = Default::default();
         let result = ffi_to_result(unsafe {
             ffi::vmaAllocateMemory(
-                self.internal,
+                self.inner.internal,
                 &ffi_requirements,
                 &create_info,
                 &mut allocation.internal,
@@ -1385,7 +3056,11 @@ impl Allocator {
             )
         });
         match result {
-            erupt::vk::Result::SUCCESS => Ok((allocation, allocation_info)),
+            erupt::vk::Result::SUCCESS => {
+                self.track_pool_allocations(create_info.pool, [allocation]);
+                self.log_allocated([allocation]);
+                Ok((allocation, allocation_info))
+            }
             _ => Err(Error::vulkan(result)),
         }
     }
@@ -1405,37 +3080,48 @@ impl Allocator {
         allocation_info: &AllocationCreateInfo,
         allocation_count: usize,
     ) -> Result<Vec<(Allocation, AllocationInfo)>> {
+        self.check_alive()?;
         let ffi_requirements = unsafe {
             mem::transmute::<erupt::vk::MemoryRequirements, ffi::VkMemoryRequirements>(
                 *memory_requirements,
             )
         };
+        self.validate_allocation_create_info(allocation_info)?;
         let create_info = allocation_create_info_to_ffi(allocation_info);
         let mut allocations: Vec<ffi::VmaAllocation> = vec![std::ptr::null_mut(); allocation_count];
-        let mut allocation_info: Vec<ffi::VmaAllocationInfo> =
-            vec![Default::default(); allocation_count];
+        // VMA fills `pAllocationInfo` by querying each allocation after the fact, so do the same
+        // directly into the returned pairs instead of going through a second temporary array.
         let result = ffi_to_result(unsafe {
             ffi::vmaAllocateMemoryPages(
-                self.internal,
+                self.inner.internal,
                 &ffi_requirements,
                 &create_info,
                 allocation_count,
                 allocations.as_mut_ptr(),
-                allocation_info.as_mut_ptr(),
+                std::ptr::null_mut(),
             )
         });
         match result {
             erupt::vk::Result::SUCCESS => {
-                let it = allocations.iter().zip(allocation_info.iter());
-                let allocations: Vec<(Allocation, AllocationInfo)> = it
-                    .map(|(alloc, info)| {
-                        (
-                            Allocation { internal: *alloc },
-                            AllocationInfo { internal: *info },
-                        )
+                self.track_pool_allocations(
+                    create_info.pool,
+                    allocations.iter().map(|&internal| Allocation { internal }),
+                );
+                self.log_allocated(allocations.iter().map(|&internal| Allocation { internal }));
+                Ok(allocations
+                    .into_iter()
+                    .map(|internal| {
+                        let mut allocation_info: AllocationInfo = Default::default();
+                        unsafe {
+                            ffi::vmaGetAllocationInfo(
+                                self.inner.internal,
+                                internal,
+                                &mut allocation_info.internal,
+                            );
+                        }
+                        (Allocation { internal }, allocation_info)
                     })
-                    .collect();
-                Ok(allocations)
+                    .collect())
             }
             _ => Err(Error::vulkan(result)),
         }
@@ -1449,13 +3135,15 @@ impl Allocator {
         buffer: erupt::vk::Buffer,
         allocation_info: &AllocationCreateInfo,
     ) -> Result<(Allocation, AllocationInfo)> {
+        self.check_alive()?;
         let ffi_buffer = buffer.to_raw() as ffi::VkBuffer;
+        self.validate_allocation_create_info(allocation_info)?;
         let create_info = allocation_create_info_to_ffi(allocation_info);
         let mut allocation: Allocation = Default::default();
         let mut allocation_info: AllocationInfo = Default::default();
         let result = ffi_to_result(unsafe {
             ffi::vmaAllocateMemoryForBuffer(
-                self.internal,
+                self.inner.internal,
                 ffi_buffer,
                 &create_info,
                 &mut allocation.internal,
@@ -1463,7 +3151,11 @@ impl Allocator {
             )
         });
         match result {
-            erupt::vk::Result::SUCCESS => Ok((allocation, allocation_info)),
+            erupt::vk::Result::SUCCESS => {
+                self.track_pool_allocations(create_info.pool, [allocation]);
+                self.log_allocated([allocation]);
+                Ok((allocation, allocation_info))
+            }
             _ => Err(Error::vulkan(result)),
         }
     }
@@ -1476,13 +3168,15 @@ impl Allocator {
         image: erupt::vk::Image,
         allocation_info: &AllocationCreateInfo,
     ) -> Result<(Allocation, AllocationInfo)> {
+        self.check_alive()?;
         let ffi_image = image.to_raw() as ffi::VkImage;
+        self.validate_allocation_create_info(allocation_info)?;
         let create_info = allocation_create_info_to_ffi(allocation_info);
         let mut allocation: Allocation = Default::default();
         let mut allocation_info: AllocationInfo = Default::default();
         let result = ffi_to_result(unsafe {
             ffi::vmaAllocateMemoryForImage(
-                self.internal,
+                self.inner.internal,
                 ffi_image,
                 &create_info,
                 &mut allocation.internal,
@@ -1490,16 +3184,27 @@ impl Allocator {
             )
         });
         match result {
-            erupt::vk::Result::SUCCESS => Ok((allocation, allocation_info)),
+            erupt::vk::Result::SUCCESS => {
+                self.track_pool_allocations(create_info.pool, [allocation]);
+                self.log_allocated([allocation]);
+                Ok((allocation, allocation_info))
+            }
             _ => Err(Error::vulkan(result)),
         }
     }
 
     /// Frees memory previously allocated using `Allocator::allocate_memory`,
     /// `Allocator::allocate_memory_for_buffer`, or `Allocator::allocate_memory_for_image`.
+    ///
+    /// Passing a null allocation is valid and does nothing.
     pub fn free_memory(&self, allocation: &Allocation) {
+        if self.is_destroyed() {
+            return;
+        }
+        self.log_freeing([*allocation]);
+        self.untrack_pool_allocations([*allocation]);
         unsafe {
-            ffi::vmaFreeMemory(self.internal, allocation.internal);
+            ffi::vmaFreeMemory(self.inner.internal, allocation.internal);
         }
     }
 
@@ -1513,13 +3218,47 @@ impl Allocator {
     ///
     /// Allocations in 'allocations' slice can come from any memory pools and types.
     pub fn free_memory_pages(&self, allocations: &[Allocation]) {
-        let mut allocations_ffi: Vec<ffi::VmaAllocation> =
-            allocations.iter().map(|x| x.internal).collect();
+        if self.is_destroyed() {
+            return;
+        }
+        self.log_freeing(allocations.iter().copied());
+        self.untrack_pool_allocations(allocations.iter().copied());
+        // `Allocation` is a transparent wrapper around `VmaAllocation`, so the slice can be
+        // passed as is.
+        unsafe {
+            ffi::vmaFreeMemoryPages(
+                self.inner.internal,
+                allocations.len(),
+                allocations.as_ptr() as *const ffi::VmaAllocation,
+            );
+        }
+    }
+
+    /// Same as `Allocator::free_memory_pages`, but takes the allocations from an iterator, e.g.
+    /// one draining a collection, so they don't have to be gathered into a slice first.
+    pub fn free_memory_pages_iter(&self, allocations: impl IntoIterator<Item = Allocation>) {
+        if self.is_destroyed() {
+            return;
+        }
+        let allocations_ffi: Vec<ffi::VmaAllocation> = allocations
+            .into_iter()
+            .map(|allocation| allocation.internal)
+            .collect();
+        self.log_freeing(
+            allocations_ffi
+                .iter()
+                .map(|&internal| Allocation { internal }),
+        );
+        self.untrack_pool_allocations(
+            allocations_ffi
+                .iter()
+                .map(|&internal| Allocation { internal }),
+        );
         unsafe {
             ffi::vmaFreeMemoryPages(
-                self.internal,
+                self.inner.internal,
                 allocations_ffi.len(),
-                allocations_ffi.as_mut_ptr(),
+                allocations_ffi.as_ptr(),
             );
         }
     }
@@ -1537,11 +3276,17 @@ impl Allocator {
     /// you can avoid calling it too often.
     ///
     /// If you just want to check if allocation is not lost, `Allocator::touch_allocation` will work faster.
+    ///
+    /// Returns an error of kind `ErrorKind::Config` if `allocation` is null, e.g. left at its
+    /// `Default` after a failed allocation. The same applies to the functions mapping or
+    /// binding an allocation, while the ones freeing, unmapping or flushing it do nothing.
     pub fn get_allocation_info(&self, allocation: &Allocation) -> Result<AllocationInfo> {
+        self.check_alive()?;
+        check_allocation(allocation)?;
         let mut allocation_info: AllocationInfo = Default::default();
         unsafe {
             ffi::vmaGetAllocationInfo(
-                self.internal,
+                self.inner.internal,
                 allocation.internal,
                 &mut allocation_info.internal,
             )
@@ -1549,6 +3294,27 @@ impl Allocator {
         Ok(allocation_info)
     }
 
+    /// Returns `erupt::vk::MemoryPropertyFlags` of the memory type the allocation is in.
+    ///
+    /// This is just a convenience function; the same information can be obtained using
+    /// `Allocator::get_allocation_info` and `Allocator::get_memory_type_properties`.
+    pub fn get_allocation_memory_properties(
+        &self,
+        allocation: &Allocation,
+    ) -> Result<erupt::vk::MemoryPropertyFlags> {
+        self.check_alive()?;
+        check_allocation(allocation)?;
+        let mut ffi_flags: ffi::VkMemoryPropertyFlags = Default::default();
+        Ok(unsafe {
+            ffi::vmaGetAllocationMemoryProperties(
+                self.inner.internal,
+                allocation.internal,
+                &mut ffi_flags,
+            );
+            mem::transmute::<ffi::VkMemoryPropertyFlags, erupt::vk::MemoryPropertyFlags>(ffi_flags)
+        })
+    }
+
     /// Sets user data in given allocation to new value.
     ///
     /// # Safety
@@ -1568,7 +3334,24 @@ impl Allocator {
         allocation: &Allocation,
         user_data: *mut ::std::os::raw::c_void,
     ) {
-        ffi::vmaSetAllocationUserData(self.internal, allocation.internal, user_data);
+        if self.is_destroyed() || allocation.is_null() {
+            return;
+        }
+        ffi::vmaSetAllocationUserData(self.inner.internal, allocation.internal, user_data);
+    }
+
+    /// Stores `tag` in the user data of `allocation`, e.g. an index of the entity owning it, to be
+    /// read back with `AllocationInfo::allocation_tag`.
+    ///
+    /// The tag is stored in place of the user data pointer, so it overwrites any pointer set with
+    /// `AllocationCreateInfo::user_data` or `Allocator::set_allocation_user_data`.
+    pub fn set_allocation_tag(&self, allocation: &Allocation, tag: u32) {
+        // Since VMA 3.0 the user data is an opaque value; strings passed with
+        // `AllocationCreateFlags::USER_DATA_COPY_STRING` are stored as the allocation name
+        // instead, so an integer never gets dereferenced.
+        unsafe {
+            self.set_allocation_user_data(allocation, tag as usize as *mut ::std::os::raw::c_void);
+        }
     }
 
     /// Maps memory represented by given allocation and returns pointer to it.
@@ -1606,9 +3389,11 @@ impl Allocator {
     /// This function always fails when called for allocation that was created with
     /// `AllocationCreateFlags::CAN_BECOME_LOST` flag. Such allocations cannot be mapped.
     pub fn map_memory(&self, allocation: &Allocation) -> Result<*mut u8> {
+        self.check_alive()?;
+        check_allocation(allocation)?;
         let mut mapped_data: *mut ::std::os::raw::c_void = ::std::ptr::null_mut();
         let result = ffi_to_result(unsafe {
-            ffi::vmaMapMemory(self.internal, allocation.internal, &mut mapped_data)
+            ffi::vmaMapMemory(self.inner.internal, allocation.internal, &mut mapped_data)
         });
         match result {
             erupt::vk::Result::SUCCESS => Ok(mapped_data as *mut u8),
@@ -1616,51 +3401,416 @@ impl Allocator {
         }
     }
 
-    /// Unmaps memory represented by given allocation, mapped previously using `Allocator::map_memory`.
-    pub fn unmap_memory(&self, allocation: &Allocation) {
-        unsafe {
-            ffi::vmaUnmapMemory(self.internal, allocation.internal);
+    /// Same as `Allocator::map_memory`, but returns the pointer as `std::ptr::NonNull`.
+    ///
+    /// A successful mapping never yields a null pointer, so this makes that guarantee explicit
+    /// in the type. The same rules about calling `Allocator::unmap_memory` apply.
+    pub fn map_memory_nonnull(&self, allocation: &Allocation) -> Result<std::ptr::NonNull<u8>> {
+        let mapped_data = self.map_memory(allocation)?;
+        std::ptr::NonNull::new(mapped_data)
+            .ok_or_else(|| Error::internal("vmaMapMemory succeeded but returned a null pointer"))
+    }
+
+    /// Same as `Allocator::map_memory`, but also returns the size of the allocation, read right
+    /// after mapping it.
+    ///
+    /// Defragmentation can move an allocation, so a size cached before it may be stale; the one
+    /// returned here always describes the mapped memory. The same rules about calling
+    /// `Allocator::unmap_memory` apply.
+    pub fn map_memory_sized(
+        &self,
+        allocation: &Allocation,
+    ) -> Result<(*mut u8, erupt::vk::DeviceSize)> {
+        let mapped_data = self.map_memory(allocation)?;
+        match self.get_allocation_info(allocation) {
+            Ok(allocation_info) => Ok((mapped_data, allocation_info.get_size())),
+            Err(err) => {
+                self.unmap_memory(allocation);
+                Err(err)
+            }
+        }
+    }
+
+    /// Maps the whole `erupt::vk::DeviceMemory` block that `allocation` lives in and returns a
+    /// pointer to the beginning of the block together with the offset of `allocation` in it.
+    ///
+    /// The library always maps whole blocks, so with a single call many allocations sharing the
+    /// block can be addressed as `block + AllocationInfo::get_offset()`, without mapping each of
+    /// them. Only do that for allocations whose `AllocationInfo::get_device_memory` is the same
+    /// as the one of `allocation`; the caller is responsible for staying within their ranges.
+    ///
+    /// The mapping is counted against `allocation`, so it must be balanced by exactly one call to
+    /// `Allocator::unmap_memory` with `allocation`, after which the pointer must not be used.
+    pub fn map_block_once(
+        &self,
+        allocation: &Allocation,
+    ) -> Result<(*mut u8, erupt::vk::DeviceSize)> {
+        let mapped_data = self.map_memory(allocation)?;
+        match self.get_allocation_info(allocation) {
+            Ok(allocation_info) => {
+                let offset = allocation_info.get_offset();
+                let block = unsafe { mapped_data.sub(offset as usize) };
+                Ok((block, offset))
+            }
+            Err(err) => {
+                self.unmap_memory(allocation);
+                Err(err)
+            }
+        }
+    }
+
+    /// Maps given allocation, passes its whole memory to `f` as a byte slice and unmaps it afterwards.
+    ///
+    /// The length of the slice is `AllocationInfo::get_size()`. The allocation is unmapped even if `f`
+    /// panics, so the mapping reference count never leaks. Works the same for allocations created
+    /// with `AllocationCreateFlags::MAPPED`, which stay mapped afterwards.
+    ///
+    /// No flushing or invalidation is done; use `Allocator::flush_allocation` and
+    /// `Allocator::invalidate_allocation` for memory types that aren't `HOST_COHERENT`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must have exclusive host access to the memory of `allocation` while `f` runs:
+    /// it must not be read or written through any other mapping, including a nested
+    /// `Allocator::with_mapped` call on the same allocation or one made from another thread.
+    pub unsafe fn with_mapped<R>(
+        &self,
+        allocation: &Allocation,
+        f: impl FnOnce(&mut [u8]) -> R,
+    ) -> Result<R> {
+        let size = self.get_allocation_info(allocation)?.get_size();
+        let ptr = self.map_memory(allocation)?;
+        let _guard = MapGuard {
+            allocator: self,
+            allocation,
+        };
+        let data = std::slice::from_raw_parts_mut(ptr, size as usize);
+        Ok(f(data))
+    }
+
+    /// Unmaps memory represented by given allocation, mapped previously using `Allocator::map_memory`.
+    pub fn unmap_memory(&self, allocation: &Allocation) {
+        if self.is_destroyed() || allocation.is_null() {
+            return;
+        }
+        unsafe {
+            ffi::vmaUnmapMemory(self.inner.internal, allocation.internal);
+        }
+    }
+
+    /// Flushes memory of given allocation.
+    ///
+    /// Calls `erupt::vk::Device::FlushMappedMemoryRanges` for memory associated with given range of given allocation.
+    ///
+    /// - `offset` must be relative to the beginning of allocation.
+    /// - `size` can be `erupt::vk::WHOLE_SIZE`. It means all memory from `offset` the the end of given allocation.
+    /// - `offset` and `size` don't have to be aligned; hey are internally rounded down/up to multiple of `nonCoherentAtomSize`.
+    /// - If `size` is 0, this call is ignored.
+    /// - If memory type that the `allocation` belongs to is not `erupt::vk::MemoryPropertyFlags::HOST_VISIBLE` or it is `erupt::vk::MemoryPropertyFlags::HOST_COHERENT`, this call is ignored.
+    pub fn flush_allocation(
+        &self,
+        allocation: &Allocation,
+        offset: erupt::vk::DeviceSize,
+        size: erupt::vk::DeviceSize,
+    ) {
+        if self.is_destroyed() || allocation.is_null() {
+            return;
+        }
+        unsafe {
+            ffi::vmaFlushAllocation(self.inner.internal, allocation.internal, offset, size);
+        }
+    }
+
+    /// Invalidates memory of given allocation.
+    ///
+    /// Calls `erupt::vk::Device::invalidate_mapped_memory_ranges` for memory associated with given range of given allocation.
+    ///
+    /// - `offset` must be relative to the beginning of allocation.
+    /// - `size` can be `erupt::vk::WHOLE_SIZE`. It means all memory from `offset` the the end of given allocation.
+    /// - `offset` and `size` don't have to be aligned. They are internally rounded down/up to multiple of `nonCoherentAtomSize`.
+    /// - If `size` is 0, this call is ignored.
+    /// - If memory type that the `allocation` belongs to is not `erupt::vk::MemoryPropertyFlags::HOST_VISIBLE` or it is `erupt::vk::MemoryPropertyFlags::HOST_COHERENT`, this call is ignored.
+    pub fn invalidate_allocation(
+        &self,
+        allocation: &Allocation,
+        offset: erupt::vk::DeviceSize,
+        size: erupt::vk::DeviceSize,
+    ) {
+        if self.is_destroyed() || allocation.is_null() {
+            return;
+        }
+        unsafe {
+            ffi::vmaInvalidateAllocation(self.inner.internal, allocation.internal, offset, size);
+        }
+    }
+
+    /// Copies `data` into the memory of given allocation, starting at `offset` bytes from its beginning.
+    ///
+    /// If the allocation is persistently mapped, e.g. created with `AllocationCreateFlags::MAPPED`,
+    /// the existing pointer is reused. Otherwise the allocation is mapped for the duration of the copy
+    /// and unmapped afterwards. The written range is flushed, which is a no-op for `HOST_COHERENT` memory.
+    ///
+    /// Returns an error of kind `ErrorKind::Memory` without writing anything if the range
+    /// doesn't fit within `AllocationInfo::get_size()`.
+    ///
+    /// The allocation must be in `erupt::vk::MemoryPropertyFlags::HOST_VISIBLE` memory.
+    pub fn write_bytes(
+        &self,
+        allocation: &Allocation,
+        offset: erupt::vk::DeviceSize,
+        data: &[u8],
+    ) -> Result<()> {
+        self.write_bytes_with(allocation, offset, data, FlushRange::Accessed)
+    }
+
+    /// Same as `Allocator::write_bytes`, but flushes `flush` instead of only the written range.
+    ///
+    /// Pass offset 0 and `FlushRange::Whole` to fill the allocation from its beginning without
+    /// caring about the exact range that gets flushed.
+    pub fn write_bytes_with(
+        &self,
+        allocation: &Allocation,
+        offset: erupt::vk::DeviceSize,
+        data: &[u8],
+        flush: FlushRange,
+    ) -> Result<()> {
+        let allocation_info = self.checked_allocation_range(allocation, offset, data.len())?;
+        let mapped_data = allocation_info.get_mapped_data();
+        let ptr = if mapped_data.is_null() {
+            self.map_memory(allocation)?
+        } else {
+            mapped_data
+        };
+        unsafe {
+            std::ptr::copy_nonoverlapping(data.as_ptr(), ptr.add(offset as usize), data.len());
+        }
+        let (flush_offset, flush_size) = flush.resolve(offset, data.len());
+        self.flush_allocation(allocation, flush_offset, flush_size);
+        if mapped_data.is_null() {
+            self.unmap_memory(allocation);
+        }
+        Ok(())
+    }
+
+    /// Same as `Allocator::write_bytes` at offset 0, i.e. copies `data` to the beginning of the
+    /// allocation, e.g. to fill a buffer that is exactly as big as `data`.
+    pub fn write_from_start(&self, allocation: &Allocation, data: &[u8]) -> Result<()> {
+        self.write_bytes(allocation, 0, data)
+    }
+
+    /// Copies memory of given allocation, starting at `offset` bytes from its beginning, into `out`.
+    ///
+    /// The range is invalidated before reading, which is a no-op for `HOST_COHERENT` memory.
+    /// Mapping works the same way as in `Allocator::write_bytes`.
+    ///
+    /// Returns an error of kind `ErrorKind::Memory` without reading anything if the range
+    /// doesn't fit within `AllocationInfo::get_size()`.
+    pub fn read_bytes(
+        &self,
+        allocation: &Allocation,
+        offset: erupt::vk::DeviceSize,
+        out: &mut [u8],
+    ) -> Result<()> {
+        self.read_bytes_with(allocation, offset, out, FlushRange::Accessed)
+    }
+
+    /// Same as `Allocator::read_bytes` at offset 0, i.e. fills `out` with the first `out.len()`
+    /// bytes of the allocation.
+    pub fn read_from_start(&self, allocation: &Allocation, out: &mut [u8]) -> Result<()> {
+        self.read_bytes(allocation, 0, out)
+    }
+
+    /// Same as `Allocator::read_bytes`, but invalidates `invalidate` instead of only the read range.
+    pub fn read_bytes_with(
+        &self,
+        allocation: &Allocation,
+        offset: erupt::vk::DeviceSize,
+        out: &mut [u8],
+        invalidate: FlushRange,
+    ) -> Result<()> {
+        let allocation_info = self.checked_allocation_range(allocation, offset, out.len())?;
+        let mapped_data = allocation_info.get_mapped_data();
+        let ptr = if mapped_data.is_null() {
+            self.map_memory(allocation)?
+        } else {
+            mapped_data
+        };
+        let (invalidate_offset, invalidate_size) = invalidate.resolve(offset, out.len());
+        self.invalidate_allocation(allocation, invalidate_offset, invalidate_size);
+        unsafe {
+            std::ptr::copy_nonoverlapping(ptr.add(offset as usize), out.as_mut_ptr(), out.len());
+        }
+        if mapped_data.is_null() {
+            self.unmap_memory(allocation);
+        }
+        Ok(())
+    }
+
+    /// Typed version of `Allocator::write_bytes`, writing `data` at `offset` bytes from the beginning
+    /// of the allocation.
+    ///
+    /// The byte size of the write is `size_of::<T>() * data.len()`.
+    #[cfg(feature = "bytemuck")]
+    pub fn write_slice<T: bytemuck::Pod>(
+        &self,
+        allocation: &Allocation,
+        offset: erupt::vk::DeviceSize,
+        data: &[T],
+    ) -> Result<()> {
+        self.write_bytes(allocation, offset, bytemuck::cast_slice(data))
+    }
+
+    /// Typed version of `Allocator::read_bytes`, filling `out` with memory starting at `offset` bytes
+    /// from the beginning of the allocation.
+    ///
+    /// The byte size of the read is `size_of::<T>() * out.len()`.
+    #[cfg(feature = "bytemuck")]
+    pub fn read_slice<T: bytemuck::Pod>(
+        &self,
+        allocation: &Allocation,
+        offset: erupt::vk::DeviceSize,
+        out: &mut [T],
+    ) -> Result<()> {
+        self.read_bytes(allocation, offset, bytemuck::cast_slice_mut(out))
+    }
+
+    /// Flushes element `index` of an array of `T` stored at the beginning of the allocation, i.e.
+    /// `size_of::<T>()` bytes at offset `index * size_of::<T>()`, e.g. after updating a single
+    /// struct in a mapped buffer. The range is rounded to `nonCoherentAtomSize` as described in
+    /// `Allocator::flush_allocation`.
+    ///
+    /// Returns an error of kind `ErrorKind::Memory` without flushing anything if the element
+    /// doesn't fit within `AllocationInfo::get_size()`.
+    pub fn flush_range_of<T>(&self, allocation: &Allocation, index: usize) -> Result<()> {
+        let (offset, size) = self.checked_element_range::<T>(allocation, index)?;
+        self.flush_allocation(allocation, offset, size);
+        Ok(())
+    }
+
+    /// Invalidates element `index` of an array of `T` stored at the beginning of the allocation,
+    /// the counterpart of `Allocator::flush_range_of` for reading what the device wrote.
+    ///
+    /// Returns an error of kind `ErrorKind::Memory` without invalidating anything if the element
+    /// doesn't fit within `AllocationInfo::get_size()`.
+    pub fn invalidate_range_of<T>(&self, allocation: &Allocation, index: usize) -> Result<()> {
+        let (offset, size) = self.checked_element_range::<T>(allocation, index)?;
+        self.invalidate_allocation(allocation, offset, size);
+        Ok(())
+    }
+
+    /// Returns the offset and size of element `index` of an array of `T` if it fits within the
+    /// allocation.
+    fn checked_element_range<T>(
+        &self,
+        allocation: &Allocation,
+        index: usize,
+    ) -> Result<(erupt::vk::DeviceSize, erupt::vk::DeviceSize)> {
+        let size = mem::size_of::<T>();
+        let offset = index.checked_mul(size).ok_or_else(|| {
+            Error::memory(format!(
+                "offset of element {} of {} bytes overflows",
+                index, size
+            ))
+        })? as erupt::vk::DeviceSize;
+        self.checked_allocation_range(allocation, offset, size)?;
+        Ok((offset, size as erupt::vk::DeviceSize))
+    }
+
+    /// Returns information about the allocation if `len` bytes starting at `offset` fit within it.
+    fn checked_allocation_range(
+        &self,
+        allocation: &Allocation,
+        offset: erupt::vk::DeviceSize,
+        len: usize,
+    ) -> Result<AllocationInfo> {
+        let allocation_info = self.get_allocation_info(allocation)?;
+        match offset.checked_add(len as erupt::vk::DeviceSize) {
+            Some(end) if end <= allocation_info.get_size() => Ok(allocation_info),
+            _ => Err(Error::memory(format!(
+                "range of {} bytes at offset {} exceeds allocation size {}",
+                len,
+                offset,
+                allocation_info.get_size()
+            ))),
         }
     }
 
-    /// Flushes memory of given allocation.
-    ///
-    /// Calls `erupt::vk::Device::FlushMappedMemoryRanges` for memory associated with given range of given allocation.
-    ///
-    /// - `offset` must be relative to the beginning of allocation.
-    /// - `size` can be `erupt::vk::WHOLE_SIZE`. It means all memory from `offset` the the end of given allocation.
-    /// - `offset` and `size` don't have to be aligned; hey are internally rounded down/up to multiple of `nonCoherentAtomSize`.
-    /// - If `size` is 0, this call is ignored.
-    /// - If memory type that the `allocation` belongs to is not `erupt::vk::MemoryPropertyFlags::HOST_VISIBLE` or it is `erupt::vk::MemoryPropertyFlags::HOST_COHERENT`, this call is ignored.
-    pub fn flush_allocation(&self, allocation: &Allocation, offset: usize, size: usize) {
-        unsafe {
-            ffi::vmaFlushAllocation(
-                self.internal,
-                allocation.internal,
-                offset as ffi::VkDeviceSize,
-                size as ffi::VkDeviceSize,
-            );
+    /// Rejects contradicting `AllocationCreateInfo::flags` that VMA would otherwise report with an
+    /// assert or an opaque Vulkan error, and memory type restrictions that a custom pool would
+    /// silently ignore. Only done in debug builds, except for the priority check of
+    /// `Allocator::check_priority`.
+    fn validate_allocation_create_info(&self, info: &AllocationCreateInfo) -> Result<()> {
+        self.check_priority(info.priority)?;
+        if !cfg!(debug_assertions) {
+            return Ok(());
+        }
+        if info.pool.is_some() && info.memory_type_bits != 0 {
+            return Err(Error::config(
+                "memory_type_bits is ignored for allocations in a custom pool",
+            ));
+        }
+        if info.flags.contains(AllocationCreateFlags::DEDICATED_MEMORY) {
+            if info.pool.is_some() {
+                return Err(Error::config(
+                    "DEDICATED_MEMORY can't be used together with a custom pool",
+                ));
+            }
+            if info.flags.contains(AllocationCreateFlags::NEVER_ALLOCATE) {
+                return Err(Error::config(
+                    "DEDICATED_MEMORY can't be used together with NEVER_ALLOCATE",
+                ));
+            }
+        }
+        if info.flags.contains(AllocationCreateFlags::UPPER_ADDRESS) {
+            let linear = info.pool.as_ref().is_some_and(|pool| {
+                self.inner
+                    .pools
+                    .lock()
+                    .unwrap()
+                    .get(pool)
+                    .is_none_or(|pool_info| {
+                        pool_info
+                            .flags
+                            .contains(AllocatorPoolCreateFlags::LINEAR_ALGORITHM)
+                    })
+            });
+            if !linear {
+                return Err(Error::config(
+                    "UPPER_ADDRESS can only be used in a custom pool created with LINEAR_ALGORITHM",
+                ));
+            }
         }
+        Ok(())
     }
 
-    /// Invalidates memory of given allocation.
-    ///
-    /// Calls `erupt::vk::Device::invalidate_mapped_memory_ranges` for memory associated with given range of given allocation.
+    /// Rejects a `priority` outside of [0.0, 1.0], or NaN, with an error of kind `ErrorKind::Config`.
     ///
-    /// - `offset` must be relative to the beginning of allocation.
-    /// - `size` can be `erupt::vk::WHOLE_SIZE`. It means all memory from `offset` the the end of given allocation.
-    /// - `offset` and `size` don't have to be aligned. They are internally rounded down/up to multiple of `nonCoherentAtomSize`.
-    /// - If `size` is 0, this call is ignored.
-    /// - If memory type that the `allocation` belongs to is not `erupt::vk::MemoryPropertyFlags::HOST_VISIBLE` or it is `erupt::vk::MemoryPropertyFlags::HOST_COHERENT`, this call is ignored.
-    pub fn invalidate_allocation(&self, allocation: &Allocation, offset: usize, size: usize) {
-        unsafe {
-            ffi::vmaInvalidateAllocation(
-                self.internal,
-                allocation.internal,
-                offset as ffi::VkDeviceSize,
-                size as ffi::VkDeviceSize,
-            );
+    /// Also reports a non-zero `priority` that has no effect because the allocator was created
+    /// without `AllocatorCreateFlags::EXT_MEMORY_PRIORITY`. With the `strict_priority` feature
+    /// this is an error of kind `ErrorKind::Config`, otherwise a `log::warn!` with the `log`
+    /// feature.
+    fn check_priority(&self, priority: f32) -> Result<()> {
+        if !(0.0..=1.0).contains(&priority) {
+            return Err(Error::config(format!(
+                "priority {} is outside of the range [0.0, 1.0]",
+                priority
+            )));
+        }
+        if priority == 0.0
+            || self
+                .inner
+                .create_flags
+                .contains(AllocatorCreateFlags::EXT_MEMORY_PRIORITY)
+        {
+            return Ok(());
         }
+        let message = "priority is ignored unless the allocator is created with AllocatorCreateFlags::EXT_MEMORY_PRIORITY";
+        if cfg!(feature = "strict_priority") {
+            return Err(Error::config(message));
+        }
+        #[cfg(feature = "log")]
+        log::warn!("{}", message);
+        Ok(())
     }
 
     /// Checks magic number in margins around all allocations in given memory types (in both default and custom pools) in search for corruptions.
@@ -1669,16 +3819,20 @@ impl Allocator {
     ///
     /// Corruption detection is enabled only when `VMA_DEBUG_DETECT_CORRUPTION` macro is defined to nonzero,
     /// `VMA_DEBUG_MARGIN` is defined to nonzero and only for memory types that are `HOST_VISIBLE` and `HOST_COHERENT`.
+    /// The `corruption_detection` feature of this crate defines both macros.
     ///
     /// Possible error values:
     ///
     /// - `erupt::vk::Result::ERROR_FEATURE_NOT_PRESENT` - corruption detection is not enabled for any of specified memory types.
-    /// - `erupt::vk::Result::ERROR_VALIDATION_FAILED_EXT` - corruption detection has been performed and found memory corruptions around one of the allocations.
-    ///   `VMA_ASSERT` is also fired in that case.
+    /// - `erupt::vk::Result::ERROR_UNKNOWN` - corruption detection has been performed and found memory corruptions around one of the allocations.
+    ///   With the `corruption_detection` feature the `VMA_ASSERT` fired in that case only prints a message to stderr
+    ///   instead of aborting the process.
     /// - Other value: Error returned by Vulkan, e.g. memory mapping failure.
-    pub fn check_corruption(&self, memory_types: erupt::vk::MemoryPropertyFlags) -> Result<()> {
-        let result =
-            ffi_to_result(unsafe { ffi::vmaCheckCorruption(self.internal, memory_types.bits()) });
+    pub fn check_corruption(&self, memory_type_bits: u32) -> Result<()> {
+        self.check_alive()?;
+        let result = ffi_to_result(unsafe {
+            ffi::vmaCheckCorruption(self.inner.internal, memory_type_bits)
+        });
         match result {
             erupt::vk::Result::SUCCESS => Ok(()),
             _ => Err(Error::vulkan(result)),
@@ -1687,9 +3841,9 @@ impl Allocator {
 
     /// Begins defragmentation process.
     ///
-    /// Use this function instead of old, deprecated `Allocator::defragment`.
+    /// For a simpler interface that runs all the passes at once, see `Allocator::defragment`.
     ///
-    /// Warning! Between the call to `Allocator::defragmentation_begin` and `Allocator::defragmentation_end`.
+    /// Warning! Between the call to `Allocator::begin_defragmentation` and `Allocator::end_defragmentation`.
     ///
     /// - You should not use any of allocations passed as `allocations` or
     /// any allocations that belong to pools passed as `pools`,
@@ -1790,11 +3944,32 @@ impl Allocator {
     using VmaAllocationInfo::pMappedData.
 
     Note: Defragmentation is not supported in custom pools created with #VMA_POOL_CREATE_LINEAR_ALGORITHM_BIT.
+    Passing such a pool, if it was created with `Allocator::create_pool`, returns an error of kind
+    `ErrorKind::Unsupported` without calling into VMA.
      */
     pub fn begin_defragmentation(
         &self,
         info: &DefragmentationInfo,
     ) -> Result<DefragmentationContext> {
+        self.check_alive()?;
+        if let Some(pool) = &info.pool {
+            let linear = self
+                .inner
+                .pools
+                .lock()
+                .unwrap()
+                .get(pool)
+                .is_some_and(|pool_info| {
+                    pool_info
+                        .flags
+                        .contains(AllocatorPoolCreateFlags::LINEAR_ALGORITHM)
+                });
+            if linear {
+                return Err(Error::unsupported(
+                    "defragmentation of pools created with LINEAR_ALGORITHM is not supported",
+                ));
+            }
+        }
         let mut context = DefragmentationContext {
             internal: std::ptr::null_mut(),
         };
@@ -1809,7 +3984,7 @@ impl Allocator {
             maxAllocationsPerPass: info.max_allocations_per_pass,
         };
         let result = ffi_to_result(unsafe {
-            ffi::vmaBeginDefragmentation(self.internal, &ffi_info, &mut context.internal)
+            ffi::vmaBeginDefragmentation(self.inner.internal, &ffi_info, &mut context.internal)
         });
         match result {
             erupt::vk::Result::SUCCESS => Ok(context),
@@ -1818,13 +3993,17 @@ impl Allocator {
     }
 
     /// Starts single defragmentation pass.
-    pub fn begin_defragmentation_pass(
+    ///
+    /// The returned moves borrow `context` until they are dropped, which makes it impossible to
+    /// begin the next pass before ending this one with `Allocator::end_defragmentation_pass`.
+    pub fn begin_defragmentation_pass<'a>(
         &self,
-        context: &mut DefragmentationContext,
-    ) -> Result<DefragmentationPassResult> {
+        context: &'a mut DefragmentationContext,
+    ) -> Result<DefragmentationPassResult<'a>> {
+        self.check_alive()?;
         let mut ffi_moves = ffi::VmaDefragmentationPassMoveInfo::default();
         let result = ffi_to_result(unsafe {
-            ffi::vmaBeginDefragmentationPass(self.internal, context.internal, &mut ffi_moves)
+            ffi::vmaBeginDefragmentationPass(self.inner.internal, context.internal, &mut ffi_moves)
         });
         match result {
             erupt::vk::Result::INCOMPLETE => {
@@ -1844,6 +4023,7 @@ impl Allocator {
                 }
                 Ok(DefragmentationPassResult::Incomplete(
                     DefragmentationPassMoveInfo {
+                        context,
                         internal: ffi_moves,
                         moves: moves.into(),
                     },
@@ -1864,9 +4044,9 @@ impl Allocator {
     /// If no more moves are possible you can end whole defragmentation.
     pub fn end_defragmentation_pass(
         &self,
-        context: &mut DefragmentationContext,
         moves: &mut DefragmentationPassMoveInfo,
     ) -> Result<bool> {
+        self.check_alive()?;
         for (i, mov) in moves.moves.iter().enumerate() {
             unsafe {
                 let ffi_mov = moves.internal.pMoves.add(i);
@@ -1874,8 +4054,26 @@ impl Allocator {
                     .write(mov.operation as ffi::VmaDefragmentationMoveOperation);
             }
         }
+        self.log_freeing(
+            moves
+                .moves
+                .iter()
+                .filter(|mov| mov.operation == DefragmentationMoveOperation::Destroy)
+                .map(|mov| mov.src_allocation),
+        );
+        self.untrack_pool_allocations(
+            moves
+                .moves
+                .iter()
+                .filter(|mov| mov.operation == DefragmentationMoveOperation::Destroy)
+                .map(|mov| mov.src_allocation),
+        );
         let result = ffi_to_result(unsafe {
-            ffi::vmaEndDefragmentationPass(self.internal, context.internal, &mut moves.internal)
+            ffi::vmaEndDefragmentationPass(
+                self.inner.internal,
+                moves.context.internal,
+                &mut moves.internal,
+            )
         });
         if !(result == erupt::vk::Result::SUCCESS || result == erupt::vk::Result::INCOMPLETE) {
             return Err(Error::vulkan(result));
@@ -1897,9 +4095,17 @@ impl Allocator {
         &self,
         context: &mut DefragmentationContext,
     ) -> DefragmentationStats {
+        if self.is_destroyed() {
+            return DefragmentationStats {
+                bytes_moved: 0,
+                bytes_freed: 0,
+                allocations_moved: 0,
+                device_memory_blocks_freed: 0,
+            };
+        }
         let mut ffi_stats = ffi::VmaDefragmentationStats::default();
         unsafe {
-            ffi::vmaEndDefragmentation(self.internal, context.internal, &mut ffi_stats);
+            ffi::vmaEndDefragmentation(self.inner.internal, context.internal, &mut ffi_stats);
         }
         DefragmentationStats {
             bytes_moved: ffi_stats.bytesMoved as usize,
@@ -1909,6 +4115,167 @@ impl Allocator {
         }
     }
 
+    /// Performs whole defragmentation process, running passes until no more moves are possible.
+    ///
+    /// `on_move` is called for every move of every pass and returns the operation to perform for it.
+    /// Inside it, you should record and execute copying of the resource from
+    /// `DefragmentationMove::src_allocation` to `DefragmentationMove::dst_tmp_allocation`,
+    /// bind a new resource to the latter and destroy the old resource, but not its allocation,
+    /// exactly as you would between `Allocator::begin_defragmentation_pass` and
+    /// `Allocator::end_defragmentation_pass`. The copy must have finished executing before `on_move` returns.
+    ///
+    /// Defragmentation is ended even if one of the passes fails, in which case the error is returned.
+    pub fn defragment(
+        &self,
+        info: &DefragmentationInfo,
+        mut on_move: impl FnMut(&DefragmentationMove) -> DefragmentationMoveOperation,
+    ) -> Result<DefragmentationStats> {
+        let mut context = self.begin_defragmentation(info)?;
+        let result = self.run_defragmentation_passes(&mut context, false, &mut on_move);
+        let stats = self.end_defragmentation(&mut context);
+        result.map(|()| stats)
+    }
+
+    /// Same as `Allocator::defragment`, but the contents of allocations in
+    /// `erupt::vk::MemoryPropertyFlags::HOST_VISIBLE` memory are copied on the CPU after
+    /// `on_move` returns `DefragmentationMoveOperation::Copy` for them.
+    ///
+    /// For such moves `on_move` only has to recreate the resource, bind it to
+    /// `DefragmentationMove::dst_tmp_allocation` and destroy the old one. Moves in memory that
+    /// isn't host visible still need the copy recorded and executed by `on_move` on the GPU.
+    /// `on_move` can tell the two cases apart with `Allocator::get_allocation_memory_properties`.
+    /// Moves for which `on_move` returns `Ignore` or `Destroy` aren't copied.
+    ///
+    /// If a CPU copy fails, the remaining moves of the pass are ignored, the pass and the
+    /// defragmentation are ended and the error is returned.
+    ///
+    /// The CPU copy is fastest in `HOST_CACHED` memory, as reading uncached memory is slow.
+    pub fn defragment_with_host_copy(
+        &self,
+        info: &DefragmentationInfo,
+        mut on_move: impl FnMut(&DefragmentationMove) -> DefragmentationMoveOperation,
+    ) -> Result<DefragmentationStats> {
+        let mut context = self.begin_defragmentation(info)?;
+        let result = self.run_defragmentation_passes(&mut context, true, &mut on_move);
+        let stats = self.end_defragmentation(&mut context);
+        result.map(|()| stats)
+    }
+
+    /// Defragments each of the custom `pools` in turn, running a separate `Allocator::defragment`
+    /// session for every one of them, and returns the statistics summed over all sessions.
+    ///
+    /// `info.pool` is ignored and replaced with each of `pools`. If a session fails, the error is
+    /// returned right away and the remaining pools are not defragmented.
+    pub fn defragment_pools(
+        &self,
+        pools: &[AllocatorPool],
+        info: &DefragmentationInfo,
+        mut on_move: impl FnMut(&DefragmentationMove) -> DefragmentationMoveOperation,
+    ) -> Result<DefragmentationStats> {
+        let mut total = DefragmentationStats {
+            bytes_moved: 0,
+            bytes_freed: 0,
+            allocations_moved: 0,
+            device_memory_blocks_freed: 0,
+        };
+        for pool in pools {
+            let pool_info = DefragmentationInfo {
+                pool: Some(pool.clone()),
+                ..info.clone()
+            };
+            let stats = self.defragment(&pool_info, &mut on_move)?;
+            total.bytes_moved += stats.bytes_moved;
+            total.bytes_freed += stats.bytes_freed;
+            total.allocations_moved += stats.allocations_moved;
+            total.device_memory_blocks_freed += stats.device_memory_blocks_freed;
+        }
+        Ok(total)
+    }
+
+    /// Runs defragmentation passes of `Allocator::defragment` until no more moves are possible.
+    fn run_defragmentation_passes(
+        &self,
+        context: &mut DefragmentationContext,
+        host_copy: bool,
+        on_move: &mut impl FnMut(&DefragmentationMove) -> DefragmentationMoveOperation,
+    ) -> Result<()> {
+        loop {
+            match self.begin_defragmentation_pass(context)? {
+                DefragmentationPassResult::Success => return Ok(()),
+                DefragmentationPassResult::Incomplete(mut moves) => {
+                    // The pass must be ended even if processing its moves fails.
+                    let result =
+                        self.process_defragmentation_moves(moves.moves_mut(), host_copy, on_move);
+                    let finished = self.end_defragmentation_pass(&mut moves);
+                    result?;
+                    if finished? {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Calls `on_move` for every move of a pass and, if `host_copy` is set, copies the contents
+    /// of the moves it left as `DefragmentationMoveOperation::Copy` on the CPU.
+    ///
+    /// If a copy fails, that move and all the remaining ones are set to
+    /// `DefragmentationMoveOperation::Ignore` so the pass can still be ended.
+    fn process_defragmentation_moves(
+        &self,
+        moves: &mut [DefragmentationMove],
+        host_copy: bool,
+        on_move: &mut impl FnMut(&DefragmentationMove) -> DefragmentationMoveOperation,
+    ) -> Result<()> {
+        let mut result = Ok(());
+        for mov in moves {
+            if result.is_err() {
+                mov.operation = DefragmentationMoveOperation::Ignore;
+                continue;
+            }
+            mov.operation = on_move(mov);
+            if host_copy && mov.operation == DefragmentationMoveOperation::Copy {
+                if let Err(err) = self.copy_moved_allocation_on_host(mov) {
+                    mov.operation = DefragmentationMoveOperation::Ignore;
+                    result = Err(err);
+                }
+            }
+        }
+        result
+    }
+
+    /// Copies the contents of a move's source allocation to its destination if it's in
+    /// `erupt::vk::MemoryPropertyFlags::HOST_VISIBLE` memory.
+    fn copy_moved_allocation_on_host(&self, mov: &DefragmentationMove) -> Result<()> {
+        if self
+            .get_allocation_memory_properties(&mov.src_allocation)?
+            .contains(erupt::vk::MemoryPropertyFlags::HOST_VISIBLE)
+        {
+            self.copy_allocation_on_host(&mov.src_allocation, &mov.dst_tmp_allocation)?;
+        }
+        Ok(())
+    }
+
+    /// Copies the whole contents of `src` into `dst`, which must be at least as big.
+    fn copy_allocation_on_host(&self, src: &Allocation, dst: &Allocation) -> Result<()> {
+        let (src_data, size) = self.map_memory_sized(src)?;
+        let _src_guard = MapGuard {
+            allocator: self,
+            allocation: src,
+        };
+        let dst_data = self.map_memory(dst)?;
+        let _dst_guard = MapGuard {
+            allocator: self,
+            allocation: dst,
+        };
+        self.invalidate_allocation(src, 0, erupt::vk::WHOLE_SIZE);
+        unsafe {
+            std::ptr::copy_nonoverlapping(src_data, dst_data, size as usize);
+        }
+        self.flush_allocation(dst, 0, erupt::vk::WHOLE_SIZE);
+        Ok(())
+    }
+
     /// Binds buffer to allocation.
     ///
     /// Binds specified buffer to region of memory represented by specified allocation.
@@ -1927,9 +4294,11 @@ impl Allocator {
         buffer: erupt::vk::Buffer,
         allocation: &Allocation,
     ) -> Result<()> {
+        self.check_alive()?;
+        check_allocation(allocation)?;
         let result = ffi_to_result(unsafe {
             ffi::vmaBindBufferMemory(
-                self.internal,
+                self.inner.internal,
                 allocation.internal,
                 buffer.to_raw() as ffi::VkBuffer,
             )
@@ -1958,9 +4327,11 @@ impl Allocator {
         image: erupt::vk::Image,
         allocation: &Allocation,
     ) -> Result<()> {
+        self.check_alive()?;
+        check_allocation(allocation)?;
         let result = ffi_to_result(unsafe {
             ffi::vmaBindImageMemory(
-                self.internal,
+                self.inner.internal,
                 allocation.internal,
                 image.to_raw() as ffi::VkImage,
             )
@@ -1984,21 +4355,25 @@ impl Allocator {
     /// and if dedicated allocation is possible (AllocationCreateInfo::pool is null
     /// and `AllocationCreateFlags::NEVER_ALLOCATE` is not used), it creates dedicated
     /// allocation for this buffer, just like when using `AllocationCreateFlags::DEDICATED_MEMORY`.
+    ///
+    /// `buffer_info.p_next` may point to a chain of extension structures, e.g.
+    /// `erupt::vk::ExternalMemoryBufferCreateInfo`. The chain is passed to Vulkan unchanged,
+    /// so every structure in it must be valid and stay alive until this function returns.
     pub fn create_buffer(
         &self,
         buffer_info: &erupt::vk::BufferCreateInfo,
         allocation_info: &AllocationCreateInfo,
     ) -> Result<(erupt::vk::Buffer, Allocation, AllocationInfo)> {
-        let buffer_create_info = unsafe {
-            mem::transmute::<erupt::vk::BufferCreateInfo, ffi::VkBufferCreateInfo>(*buffer_info)
-        };
+        self.check_alive()?;
+        let buffer_create_info = buffer_create_info_to_ffi(buffer_info);
+        self.validate_allocation_create_info(allocation_info)?;
         let allocation_create_info = allocation_create_info_to_ffi(allocation_info);
         let mut buffer: ffi::VkBuffer = std::ptr::null_mut();
         let mut allocation: Allocation = Default::default();
         let mut allocation_info: AllocationInfo = Default::default();
         let result = ffi_to_result(unsafe {
             ffi::vmaCreateBuffer(
-                self.internal,
+                self.inner.internal,
                 &buffer_create_info,
                 &allocation_create_info,
                 &mut buffer,
@@ -2007,11 +4382,315 @@ impl Allocator {
             )
         });
         match result {
-            erupt::vk::Result::SUCCESS => Ok((
-                erupt::vk::Buffer(buffer as u64),
-                allocation,
-                allocation_info,
-            )),
+            erupt::vk::Result::SUCCESS => {
+                self.track_pool_allocations(allocation_create_info.pool, [allocation]);
+                self.log_allocated([allocation]);
+                Ok((
+                    erupt::vk::Buffer(buffer as u64),
+                    allocation,
+                    allocation_info,
+                ))
+            }
+            _ => Err(Error::vulkan(result)),
+        }
+    }
+
+    /// Same as `Allocator::create_buffer`, but forces a dedicated allocation with the given
+    /// `priority`, e.g. to keep big render targets resident in device memory under pressure.
+    ///
+    /// `AllocationCreateFlags::DEDICATED_MEMORY` is added to `allocation_info.flags` and
+    /// `allocation_info.priority` is replaced with `priority`. The priority only has an effect if
+    /// the allocator was created with `AllocatorCreateFlags::EXT_MEMORY_PRIORITY`, and is
+    /// otherwise reported the same way as `AllocationCreateInfo::priority`. A priority outside of
+    /// [0.0, 1.0] is rejected with an error of kind `ErrorKind::Config`.
+    ///
+    /// VMA 3.0.1 doesn't report the priority back, e.g. in `AllocationInfo` or the stats string,
+    /// so there is no way to read it from an existing allocation.
+    pub fn create_buffer_with_priority(
+        &self,
+        buffer_info: &erupt::vk::BufferCreateInfo,
+        allocation_info: &AllocationCreateInfo,
+        priority: f32,
+    ) -> Result<(erupt::vk::Buffer, Allocation, AllocationInfo)> {
+        self.create_buffer(
+            buffer_info,
+            &dedicated_with_priority(allocation_info, priority),
+        )
+    }
+
+    /// Same as `Allocator::create_buffer`, but also returns the device address of the buffer,
+    /// e.g. for ray tracing or bindless access from shaders.
+    ///
+    /// Returns an error of kind `ErrorKind::Config` without creating anything if the allocator
+    /// wasn't created with `AllocatorCreateFlags::BUFFER_DEVICE_ADDRESS`, or if `buffer_info.usage`
+    /// doesn't contain `erupt::vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS`. Returns an error of
+    /// kind `ErrorKind::Unsupported` if neither Vulkan 1.2 nor `VK_KHR_buffer_device_address` is
+    /// enabled on the device.
+    pub fn create_buffer_with_device_address(
+        &self,
+        buffer_info: &erupt::vk::BufferCreateInfo,
+        allocation_info: &AllocationCreateInfo,
+    ) -> Result<(
+        erupt::vk::Buffer,
+        Allocation,
+        AllocationInfo,
+        erupt::vk::DeviceAddress,
+    )> {
+        if !self
+            .inner
+            .create_flags
+            .contains(AllocatorCreateFlags::BUFFER_DEVICE_ADDRESS)
+        {
+            return Err(Error::config(
+                "device addresses require an allocator created with AllocatorCreateFlags::BUFFER_DEVICE_ADDRESS",
+            ));
+        }
+        if !buffer_info
+            .usage
+            .contains(erupt::vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS)
+        {
+            return Err(Error::config(
+                "device addresses require buffers created with BufferUsageFlags::SHADER_DEVICE_ADDRESS",
+            ));
+        }
+        let device = &self.inner.device;
+        let get_buffer_device_address = device
+            .get_buffer_device_address
+            .or(device.get_buffer_device_address_khr)
+            .ok_or_else(|| {
+                Error::unsupported(
+                    "device addresses need Vulkan 1.2 or VK_KHR_buffer_device_address",
+                )
+            })?;
+        let (buffer, allocation, allocation_info) =
+            self.create_buffer(buffer_info, allocation_info)?;
+        let info = erupt::vk::BufferDeviceAddressInfoBuilder::new().buffer(buffer);
+        let address = unsafe { get_buffer_device_address(device.handle, &*info) };
+        Ok((buffer, allocation, allocation_info, address))
+    }
+
+    /// Same as `Allocator::create_buffer`, but also fills the memory of the buffer with zeros
+    /// and flushes it, e.g. for default uniform or storage buffer contents.
+    ///
+    /// The whole allocation is zeroed, which may be more than `buffer_info.size`. The memory must
+    /// end up `erupt::vk::MemoryPropertyFlags::HOST_VISIBLE`, so use `required_flags` or a
+    /// `MemoryUsage::Auto*` value with a `HOST_ACCESS_*` flag in `allocation_info`. Otherwise an
+    /// error of kind `ErrorKind::Memory` is returned; zero such buffers on the device with
+    /// `erupt::DeviceLoader::cmd_fill_buffer` instead. If zeroing fails, the buffer is destroyed
+    /// before the error is returned.
+    pub fn create_buffer_zeroed(
+        &self,
+        buffer_info: &erupt::vk::BufferCreateInfo,
+        allocation_info: &AllocationCreateInfo,
+    ) -> Result<(erupt::vk::Buffer, Allocation, AllocationInfo)> {
+        let (buffer, allocation, allocation_info) =
+            self.create_buffer(buffer_info, allocation_info)?;
+        let zeroed = self
+            .get_allocation_memory_properties(&allocation)
+            .and_then(|flags| {
+                if flags.contains(erupt::vk::MemoryPropertyFlags::HOST_VISIBLE) {
+                    // Safety: the buffer was just created, so nothing else can access its memory
+                    unsafe { self.with_mapped(&allocation, |data| data.fill(0)) }
+                } else {
+                    Err(Error::memory(
+                        "buffer memory isn't HOST_VISIBLE, zero it with a transfer command instead",
+                    ))
+                }
+            });
+        if let Err(err) = zeroed {
+            self.destroy_buffer(buffer, &allocation);
+            return Err(err);
+        }
+        self.flush_allocation(&allocation, 0, erupt::vk::WHOLE_SIZE);
+        Ok((buffer, allocation, allocation_info))
+    }
+
+    /// Creates `count` buffers described by the same `buffer_info`, allocates memory for all of
+    /// them at once using `Allocator::allocate_memory_pages` and binds each buffer to its allocation.
+    ///
+    /// Allocating all the memory in one call lets the library place the allocations next to each
+    /// other in as few memory blocks as possible, and is faster than calling
+    /// `Allocator::create_buffer` `count` times.
+    ///
+    /// Memory requirements are queried from the first buffer only and all allocations are
+    /// suballocated, so dedicated allocations required or preferred by the driver are not used.
+    /// With a `MemoryUsage::Auto*` value, the memory type is chosen once for `buffer_info` with
+    /// `Allocator::find_memory_type_index_for_buffer_info` and used for all the buffers.
+    ///
+    /// If any step fails, all buffers and allocations created so far are destroyed before the
+    /// error is returned. Each returned pair should be destroyed with `Allocator::destroy_buffer`.
+    pub fn create_buffers(
+        &self,
+        buffer_info: &erupt::vk::BufferCreateInfo,
+        allocation_info: &AllocationCreateInfo,
+        count: usize,
+    ) -> Result<Vec<(erupt::vk::Buffer, Allocation, AllocationInfo)>> {
+        self.check_alive()?;
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+        let allocation_callbacks = self.inner.allocation_callbacks.as_deref();
+        let destroy_buffers = |buffers: &[erupt::vk::Buffer]| {
+            for buffer in buffers {
+                unsafe {
+                    self.inner
+                        .device
+                        .destroy_buffer(*buffer, allocation_callbacks)
+                };
+            }
+        };
+
+        let mut buffers = Vec::with_capacity(count);
+        for _ in 0..count {
+            match unsafe {
+                self.inner
+                    .device
+                    .create_buffer(buffer_info, allocation_callbacks)
+            }
+            .result()
+            {
+                Ok(buffer) => buffers.push(buffer),
+                Err(result) => {
+                    destroy_buffers(&buffers);
+                    return Err(Error::vulkan(result));
+                }
+            }
+        }
+
+        let memory_requirements =
+            unsafe { self.inner.device.get_buffer_memory_requirements(buffers[0]) };
+        // `Allocator::allocate_memory_pages` knows nothing about the buffers, which the
+        // `MemoryUsage::Auto*` values need, so the memory type is chosen for `buffer_info` here.
+        let resolved_info;
+        let allocation_info = if allocation_info.pool.is_none()
+            && allocation_info.usage.requires_host_access_flag()
+        {
+            match self.find_memory_type_index_for_buffer_info(buffer_info, allocation_info) {
+                Ok(memory_type_index) => {
+                    resolved_info = AllocationCreateInfo {
+                        usage: MemoryUsage::Unknown,
+                        memory_type_bits: 1 << memory_type_index,
+                        ..allocation_info.clone()
+                    };
+                    &resolved_info
+                }
+                Err(err) => {
+                    destroy_buffers(&buffers);
+                    return Err(err);
+                }
+            }
+        } else {
+            allocation_info
+        };
+        let allocations =
+            match self.allocate_memory_pages(&memory_requirements, allocation_info, count) {
+                Ok(allocations) => allocations,
+                Err(err) => {
+                    destroy_buffers(&buffers);
+                    return Err(err);
+                }
+            };
+
+        for (buffer, (allocation, _)) in buffers.iter().zip(allocations.iter()) {
+            if let Err(err) = self.bind_buffer_memory(*buffer, allocation) {
+                destroy_buffers(&buffers);
+                let allocations: Vec<Allocation> = allocations
+                    .iter()
+                    .map(|(allocation, _)| *allocation)
+                    .collect();
+                self.free_memory_pages(&allocations);
+                return Err(err);
+            }
+        }
+
+        Ok(buffers
+            .into_iter()
+            .zip(allocations)
+            .map(|(buffer, (allocation, allocation_info))| (buffer, allocation, allocation_info))
+            .collect())
+    }
+
+    /// Similar to `Allocator::create_buffer` but provides additional parameter `min_alignment`
+    /// which allows to specify custom, minimum alignment to be used when placing the buffer
+    /// inside a larger memory block, which may be needed e.g. for interop with OpenGL.
+    ///
+    /// `min_alignment` must be a power of two, otherwise `erupt::vk::Result::ERROR_VALIDATION_FAILED_EXT`
+    /// is returned without calling into VMA.
+    pub fn create_buffer_with_alignment(
+        &self,
+        buffer_info: &erupt::vk::BufferCreateInfo,
+        allocation_info: &AllocationCreateInfo,
+        min_alignment: erupt::vk::DeviceSize,
+    ) -> Result<(erupt::vk::Buffer, Allocation, AllocationInfo)> {
+        self.check_alive()?;
+        if !min_alignment.is_power_of_two() {
+            return Err(Error::vulkan(
+                erupt::vk::Result::ERROR_VALIDATION_FAILED_EXT,
+            ));
+        }
+        let buffer_create_info = buffer_create_info_to_ffi(buffer_info);
+        self.validate_allocation_create_info(allocation_info)?;
+        let allocation_create_info = allocation_create_info_to_ffi(allocation_info);
+        let mut buffer: ffi::VkBuffer = std::ptr::null_mut();
+        let mut allocation: Allocation = Default::default();
+        let mut allocation_info: AllocationInfo = Default::default();
+        let result = ffi_to_result(unsafe {
+            ffi::vmaCreateBufferWithAlignment(
+                self.inner.internal,
+                &buffer_create_info,
+                &allocation_create_info,
+                min_alignment,
+                &mut buffer,
+                &mut allocation.internal,
+                &mut allocation_info.internal,
+            )
+        });
+        match result {
+            erupt::vk::Result::SUCCESS => {
+                self.track_pool_allocations(allocation_create_info.pool, [allocation]);
+                self.log_allocated([allocation]);
+                Ok((
+                    erupt::vk::Buffer(buffer as u64),
+                    allocation,
+                    allocation_info,
+                ))
+            }
+            _ => Err(Error::vulkan(result)),
+        }
+    }
+
+    /// Creates a new `erupt::vk::Buffer`, binds already created memory for it.
+    ///
+    /// This function automatically creates a buffer and binds existing allocation to it.
+    /// It doesn't create a new allocation, so the memory of `allocation` can be shared
+    /// between multiple aliasing resources.
+    ///
+    /// The buffer described by `buffer_info` must fit within `AllocationInfo::get_size()` of
+    /// `allocation`, and its memory requirements must be compatible with the allocation's memory type.
+    /// Consider creating the allocation with `AllocationCreateFlags::CAN_ALIAS`.
+    ///
+    /// The caller owns the returned buffer and must destroy it using `erupt::DeviceLoader::destroy_buffer`
+    /// before freeing the allocation. Don't use `Allocator::destroy_buffer` for it, as that would
+    /// also free the shared allocation.
+    pub fn create_aliasing_buffer(
+        &self,
+        allocation: &Allocation,
+        buffer_info: &erupt::vk::BufferCreateInfo,
+    ) -> Result<erupt::vk::Buffer> {
+        self.check_alive()?;
+        check_allocation(allocation)?;
+        let buffer_create_info = buffer_create_info_to_ffi(buffer_info);
+        let mut buffer: ffi::VkBuffer = std::ptr::null_mut();
+        let result = ffi_to_result(unsafe {
+            ffi::vmaCreateAliasingBuffer(
+                self.inner.internal,
+                allocation.internal,
+                &buffer_create_info,
+                &mut buffer,
+            )
+        });
+        match result {
+            erupt::vk::Result::SUCCESS => Ok(erupt::vk::Buffer(buffer as u64)),
             _ => Err(Error::vulkan(result)),
         }
     }
@@ -2026,10 +4705,23 @@ impl Allocator {
     /// ```
     ///
     /// It it safe to pass null as `buffer` and/or `allocation`.
+    ///
+    /// If the allocator was already destroyed, its memory was freed along with it, so only the
+    /// buffer itself is destroyed.
     pub fn destroy_buffer(&self, buffer: erupt::vk::Buffer, allocation: &Allocation) {
+        if self.is_destroyed() {
+            unsafe {
+                self.inner
+                    .device
+                    .destroy_buffer(buffer, self.inner.allocation_callbacks.as_deref());
+            }
+            return;
+        }
+        self.log_freeing([*allocation]);
+        self.untrack_pool_allocations([*allocation]);
         unsafe {
             ffi::vmaDestroyBuffer(
-                self.internal,
+                self.inner.internal,
                 buffer.to_raw() as ffi::VkBuffer,
                 allocation.internal,
             );
@@ -2050,24 +4742,29 @@ impl Allocator {
     /// and `AllocationCreateFlags::NEVER_ALLOCATE` is not used), it creates dedicated
     /// allocation for this image, just like when using `AllocationCreateFlags::DEDICATED_MEMORY`.
     ///
-    /// If `VK_ERROR_VALIDAITON_FAILED_EXT` is returned, VMA may have encountered a problem
-    /// that is not caught by the validation layers. One example is if you try to create a 0x0
-    /// image, a panic will occur and `VK_ERROR_VALIDAITON_FAILED_EXT` is thrown.
+    /// `image_info.p_next` may point to a chain of extension structures, e.g.
+    /// `erupt::vk::ExternalMemoryImageCreateInfo`. The chain is passed to Vulkan unchanged,
+    /// so every structure in it must be valid and stay alive until this function returns.
+    ///
+    /// If `erupt::vk::Result::ERROR_VALIDATION_FAILED_EXT` is returned, the image description
+    /// is invalid in a way that would otherwise trip an assert inside VMA, e.g. a 0x0 image or
+    /// zero mip levels or array layers.
     pub fn create_image(
         &self,
         image_info: &erupt::vk::ImageCreateInfo,
         allocation_info: &AllocationCreateInfo,
     ) -> Result<(erupt::vk::Image, Allocation, AllocationInfo)> {
-        let image_create_info = unsafe {
-            mem::transmute::<erupt::vk::ImageCreateInfo, ffi::VkImageCreateInfo>(*image_info)
-        };
+        self.check_alive()?;
+        validate_image_create_info(image_info)?;
+        let image_create_info = image_create_info_to_ffi(image_info);
+        self.validate_allocation_create_info(allocation_info)?;
         let allocation_create_info = allocation_create_info_to_ffi(allocation_info);
         let mut image: ffi::VkImage = std::ptr::null_mut();
         let mut allocation: Allocation = Default::default();
         let mut allocation_info: AllocationInfo = Default::default();
         let result = ffi_to_result(unsafe {
             ffi::vmaCreateImage(
-                self.internal,
+                self.inner.internal,
                 &image_create_info,
                 &allocation_create_info,
                 &mut image,
@@ -2077,12 +4774,64 @@ impl Allocator {
         });
         match result {
             erupt::vk::Result::SUCCESS => {
+                self.track_pool_allocations(allocation_create_info.pool, [allocation]);
+                self.log_allocated([allocation]);
                 Ok((erupt::vk::Image(image as u64), allocation, allocation_info))
             }
             _ => Err(Error::vulkan(result)),
         }
     }
 
+    /// Same as `Allocator::create_image`, but forces a dedicated allocation with the given
+    /// `priority`, like `Allocator::create_buffer_with_priority`.
+    pub fn create_image_with_priority(
+        &self,
+        image_info: &erupt::vk::ImageCreateInfo,
+        allocation_info: &AllocationCreateInfo,
+        priority: f32,
+    ) -> Result<(erupt::vk::Image, Allocation, AllocationInfo)> {
+        self.create_image(
+            image_info,
+            &dedicated_with_priority(allocation_info, priority),
+        )
+    }
+
+    /// Creates a new `erupt::vk::Image`, binds already created memory for it.
+    ///
+    /// This function automatically creates an image and binds existing allocation to it.
+    /// It doesn't create or free any allocation, so the memory of `allocation` can be reused
+    /// by multiple aliasing resources, e.g. transient attachments of a frame graph.
+    ///
+    /// The image described by `image_info` must fit within `AllocationInfo::get_size()` of
+    /// `allocation`, and its memory requirements must be compatible with the allocation's memory type.
+    /// Consider creating the allocation with `AllocationCreateFlags::CAN_ALIAS`.
+    ///
+    /// The caller owns the returned image and must destroy it using `erupt::DeviceLoader::destroy_image`
+    /// before freeing the allocation.
+    pub fn create_aliasing_image(
+        &self,
+        allocation: &Allocation,
+        image_info: &erupt::vk::ImageCreateInfo,
+    ) -> Result<erupt::vk::Image> {
+        self.check_alive()?;
+        check_allocation(allocation)?;
+        validate_image_create_info(image_info)?;
+        let image_create_info = image_create_info_to_ffi(image_info);
+        let mut image: ffi::VkImage = std::ptr::null_mut();
+        let result = ffi_to_result(unsafe {
+            ffi::vmaCreateAliasingImage(
+                self.inner.internal,
+                allocation.internal,
+                &image_create_info,
+                &mut image,
+            )
+        });
+        match result {
+            erupt::vk::Result::SUCCESS => Ok(erupt::vk::Image(image as u64)),
+            _ => Err(Error::vulkan(result)),
+        }
+    }
+
     /// Destroys Vulkan image and frees allocated memory.
     ///
     /// This is just a convenience function equivalent to:
@@ -2093,10 +4842,23 @@ impl Allocator {
     /// ```
     ///
     /// It it safe to pass null as `image` and/or `allocation`.
+    ///
+    /// If the allocator was already destroyed, its memory was freed along with it, so only the
+    /// image itself is destroyed.
     pub fn destroy_image(&self, image: erupt::vk::Image, allocation: &Allocation) {
+        if self.is_destroyed() {
+            unsafe {
+                self.inner
+                    .device
+                    .destroy_image(image, self.inner.allocation_callbacks.as_deref());
+            }
+            return;
+        }
+        self.log_freeing([*allocation]);
+        self.untrack_pool_allocations([*allocation]);
         unsafe {
             ffi::vmaDestroyImage(
-                self.internal,
+                self.inner.internal,
                 image.to_raw() as ffi::VkImage,
                 allocation.internal,
             );
@@ -2107,18 +4869,73 @@ impl Allocator {
     /// no other functions may be called. Useful for ensuring a specific destruction
     /// order (for example, if an Allocator is a member of something that owns the Vulkan
     /// instance and destroys it in its own Drop).
-    pub fn destroy(&mut self) {
+    ///
+    /// Without calling this function, the internal allocator is destroyed when the last clone
+    /// of this `Allocator` is dropped.
+    ///
+    /// Returns an error of kind `ErrorKind::Config` and leaves the allocator untouched if other
+    /// clones of this `Allocator` are still alive, as they would be left with a destroyed
+    /// allocator.
+    pub fn destroy(&mut self) -> Result<()> {
+        match Arc::get_mut(&mut self.inner) {
+            Some(inner) => {
+                inner.destroy();
+                Ok(())
+            }
+            None => Err(Error::config(
+                "can't destroy an allocator that is shared with other clones",
+            )),
+        }
+    }
+
+    /// Returns true if `Allocator::destroy` has been called.
+    ///
+    /// Functions of a destroyed allocator that return a `Result` fail with an error of kind
+    /// `ErrorKind::Destroyed`, and the ones that free or unmap memory do nothing.
+    pub fn is_destroyed(&self) -> bool {
+        self.inner.internal.is_null()
+    }
+
+    /// Returns an error of kind `ErrorKind::Destroyed` if `Allocator::destroy` has been called.
+    fn check_alive(&self) -> Result<()> {
+        if self.is_destroyed() {
+            Err(Error::destroyed())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Unmaps a mapped allocation when going out of scope.
+struct MapGuard<'a, S> {
+    allocator: &'a Allocator<S>,
+    allocation: &'a Allocation,
+}
+
+impl<S> Drop for MapGuard<'_, S> {
+    fn drop(&mut self) {
+        self.allocator.unmap_memory(self.allocation);
+    }
+}
+
+impl AllocatorInner {
+    fn destroy(&mut self) {
         if !self.internal.is_null() {
-            unsafe {
-                ffi::vmaDestroyAllocator(self.internal);
-                self.internal = std::ptr::null_mut();
+            if self.owned {
+                unsafe {
+                    ffi::vmaDestroyAllocator(self.internal);
+                }
             }
+            self.internal = std::ptr::null_mut();
+            // The internal allocator may call these while freeing its remaining memory blocks,
+            // so they can only be released after it's gone.
+            self.device_memory_callbacks = None;
         }
     }
 }
 
-/// Custom `Drop` implementation to clean up internal allocation instance
-impl Drop for Allocator {
+/// Custom `Drop` implementation to clean up internal allocator instance once the last clone is gone
+impl Drop for AllocatorInner {
     fn drop(&mut self) {
         self.destroy();
     }
@@ -0,0 +1,95 @@
+//! Typed view of the mapped memory of an allocation.
+
+use crate::error::{Error, Result};
+use crate::{Allocation, Allocator, Shared};
+use std::ops::{Deref, DerefMut};
+
+/// Mapped memory of an allocation viewed as a `T`, created by `Allocator::map_as`.
+///
+/// The allocation stays mapped for as long as the `MappedRef` is alive. When it's dropped, the
+/// first `size_of::<T>()` bytes are flushed, which is a no-op for `HOST_COHERENT` memory, and
+/// the allocation is unmapped.
+pub struct MappedRef<'a, T: bytemuck::Pod, S = Shared> {
+    allocator: &'a Allocator<S>,
+    allocation: Allocation,
+    data: &'a mut T,
+}
+
+impl<'a, T: bytemuck::Pod, S> MappedRef<'a, T, S> {
+    /// # Safety
+    ///
+    /// Same as `Allocator::map_as`.
+    pub(crate) unsafe fn new(allocator: &'a Allocator<S>, allocation: &Allocation) -> Result<Self> {
+        let (ptr, size) = allocator.map_memory_sized(allocation)?;
+        let check = if (size as usize) < std::mem::size_of::<T>() {
+            Err(Error::memory(format!(
+                "type of {} bytes doesn't fit in allocation of {} bytes",
+                std::mem::size_of::<T>(),
+                size
+            )))
+        } else if ptr as usize % std::mem::align_of::<T>() != 0 {
+            Err(Error::memory(format!(
+                "mapped memory isn't aligned to {} bytes",
+                std::mem::align_of::<T>()
+            )))
+        } else {
+            Ok(())
+        };
+        if let Err(err) = check {
+            allocator.unmap_memory(allocation);
+            return Err(err);
+        }
+        Ok(MappedRef {
+            allocator,
+            allocation: *allocation,
+            data: &mut *(ptr as *mut T),
+        })
+    }
+}
+
+impl<T: bytemuck::Pod, S> Deref for MappedRef<'_, T, S> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.data
+    }
+}
+
+impl<T: bytemuck::Pod, S> DerefMut for MappedRef<'_, T, S> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.data
+    }
+}
+
+impl<T: bytemuck::Pod, S> Drop for MappedRef<'_, T, S> {
+    fn drop(&mut self) {
+        self.allocator.flush_allocation(
+            &self.allocation,
+            0,
+            std::mem::size_of::<T>() as erupt::vk::DeviceSize,
+        );
+        self.allocator.unmap_memory(&self.allocation);
+    }
+}
+
+impl<S> Allocator<S> {
+    /// Maps the allocation and returns a reference to its memory viewed as a `T`, e.g. a
+    /// uniform block written by the host every frame.
+    ///
+    /// Returns an error of kind `ErrorKind::Memory` if `T` is bigger than the allocation or the
+    /// mapped pointer isn't aligned for `T`.
+    ///
+    /// The allocation must be in `erupt::vk::MemoryPropertyFlags::HOST_VISIBLE` memory.
+    ///
+    /// # Safety
+    ///
+    /// The caller must have exclusive host access to the memory of `allocation` while the
+    /// `MappedRef` is alive: it must not be read or written through any other mapping, including
+    /// another `MappedRef` of the same allocation, on this or any other thread.
+    pub unsafe fn map_as<T: bytemuck::Pod>(
+        &self,
+        allocation: &Allocation,
+    ) -> Result<MappedRef<T, S>> {
+        MappedRef::new(self, allocation)
+    }
+}
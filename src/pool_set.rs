@@ -0,0 +1,126 @@
+//! Several custom pools, one per memory type, with resources routed to them automatically.
+
+use crate::error::Result;
+use crate::{
+    Allocation, AllocationCreateInfo, AllocationInfo, Allocator, AllocatorPool,
+    AllocatorPoolCreateInfo, Shared,
+};
+use std::collections::HashMap;
+
+/// Owns one custom pool per configured memory type and places buffers and images in them.
+///
+/// This allows e.g. a different `AllocatorPoolCreateInfo::block_size` for staging memory than
+/// for device local memory, instead of a single `AllocatorCreateInfo::preferred_large_heap_block_size`
+/// for everything. `PoolSet::create_buffer` and `PoolSet::create_image` first find the memory type
+/// the resource would be allocated in, and if a pool was configured for that type, allocate from
+/// it. Resources in any other memory type are allocated from the default pools as usual.
+///
+/// Buffers and images are owned by the caller and should be destroyed with
+/// `Allocator::destroy_buffer` and `Allocator::destroy_image` before the `PoolSet` is dropped,
+/// which destroys all of its pools.
+pub struct PoolSet<'a, S = Shared> {
+    allocator: &'a Allocator<S>,
+    pools: HashMap<u32, AllocatorPool>,
+}
+
+impl<'a, S> PoolSet<'a, S> {
+    /// Creates a pool for every entry of `pool_infos`, keyed by memory type index.
+    ///
+    /// The `memory_type_index` of each `AllocatorPoolCreateInfo` is replaced by its key. If
+    /// creating any of the pools fails, the ones created before are destroyed.
+    pub fn new(
+        allocator: &'a Allocator<S>,
+        pool_infos: &HashMap<u32, AllocatorPoolCreateInfo>,
+    ) -> Result<Self> {
+        let mut pool_set = PoolSet {
+            allocator,
+            pools: HashMap::with_capacity(pool_infos.len()),
+        };
+        for (&memory_type_index, pool_info) in pool_infos {
+            // On error, dropping `pool_set` destroys the pools created so far.
+            let pool = allocator.create_pool(&AllocatorPoolCreateInfo {
+                memory_type_index,
+                ..pool_info.clone()
+            })?;
+            pool_set.pools.insert(memory_type_index, pool);
+        }
+        Ok(pool_set)
+    }
+
+    /// Creates a buffer in the pool of the memory type chosen for it, or from the default pools
+    /// if no pool was configured for that type.
+    ///
+    /// If `allocation_info.pool` is already set, the buffer is created in that pool instead.
+    pub fn create_buffer(
+        &self,
+        buffer_info: &erupt::vk::BufferCreateInfo,
+        allocation_info: &AllocationCreateInfo,
+    ) -> Result<(erupt::vk::Buffer, Allocation, AllocationInfo)> {
+        let allocation_info = if allocation_info.pool.is_none() {
+            let memory_type_index = self
+                .allocator
+                .find_memory_type_index_for_buffer_info(buffer_info, allocation_info)?;
+            self.route(memory_type_index, allocation_info)
+        } else {
+            allocation_info.clone()
+        };
+        self.allocator.create_buffer(buffer_info, &allocation_info)
+    }
+
+    /// Creates an image in the pool of the memory type chosen for it, or from the default pools
+    /// if no pool was configured for that type.
+    ///
+    /// If `allocation_info.pool` is already set, the image is created in that pool instead.
+    pub fn create_image(
+        &self,
+        image_info: &erupt::vk::ImageCreateInfo,
+        allocation_info: &AllocationCreateInfo,
+    ) -> Result<(erupt::vk::Image, Allocation, AllocationInfo)> {
+        let allocation_info = if allocation_info.pool.is_none() {
+            let memory_type_index = self
+                .allocator
+                .find_memory_type_index_for_image_info(image_info, allocation_info)?;
+            self.route(memory_type_index, allocation_info)
+        } else {
+            allocation_info.clone()
+        };
+        self.allocator.create_image(image_info, &allocation_info)
+    }
+
+    /// Returns the pool created for memory type `memory_type_index`, if any.
+    pub fn pool(&self, memory_type_index: u32) -> Option<&AllocatorPool> {
+        self.pools.get(&memory_type_index)
+    }
+
+    /// Returns an iterator over the memory type indices and pools of this set, in no
+    /// particular order.
+    pub fn pools(&self) -> impl Iterator<Item = (u32, &AllocatorPool)> {
+        self.pools
+            .iter()
+            .map(|(&memory_type_index, pool)| (memory_type_index, pool))
+    }
+
+    fn route(
+        &self,
+        memory_type_index: u32,
+        allocation_info: &AllocationCreateInfo,
+    ) -> AllocationCreateInfo {
+        match self.pools.get(&memory_type_index) {
+            // The memory type is given by the pool, so `memory_type_bits` must not be set.
+            Some(pool) => AllocationCreateInfo {
+                pool: Some(pool.clone()),
+                memory_type_bits: 0,
+                ..allocation_info.clone()
+            },
+            None => allocation_info.clone(),
+        }
+    }
+}
+
+impl<S> Drop for PoolSet<'_, S> {
+    fn drop(&mut self) {
+        for (_, pool) in self.pools.drain() {
+            self.allocator.destroy_pool(&pool);
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Ring allocator for per-frame streaming data, built on top of a linear custom pool.
+
+use crate::error::Result;
+use crate::{
+    Allocation, AllocationCreateInfo, Allocator, AllocatorPool, AllocatorPoolCreateFlags,
+    AllocatorPoolCreateInfo, Shared,
+};
+use std::collections::VecDeque;
+
+/// Sub-allocates short-lived ranges, e.g. staging or uniform data uploaded every frame, from a
+/// single memory block of a custom pool created with `AllocatorPoolCreateFlags::LINEAR_ALGORITHM`.
+///
+/// Allocations are placed one after another and are never freed individually. Instead, they are
+/// grouped by frame: `RingAllocator::end_frame` starts a new frame, and
+/// `RingAllocator::release_frame` frees all allocations of the oldest frame at once, when the GPU
+/// has finished using them. This is the ring buffer usage of the linear algorithm: once the end of
+/// the block is reached, new allocations wrap around to its beginning and continue in the space
+/// of released frames, so allocating is just a pointer bump and fragmentation can't occur.
+/// `RingAllocator::reset_frame` frees the allocations of all frames instead.
+///
+/// When the block can't fit another allocation, because the frames still in flight occupy the
+/// rest of it, `RingAllocator::alloc` returns an error of kind
+/// `ErrorKind::Vulkan(erupt::vk::Result::ERROR_OUT_OF_DEVICE_MEMORY)`. Data that may still be in
+/// use by the GPU is never overwritten.
+///
+/// The pool and any allocations still alive are freed when the `RingAllocator` is dropped.
+pub struct RingAllocator<'a, S = Shared> {
+    allocator: &'a Allocator<S>,
+    pool: AllocatorPool,
+    memory_type_index: u32,
+    size: erupt::vk::DeviceSize,
+    /// End of the most recent allocation
+    head: erupt::vk::DeviceSize,
+    /// Allocations with their offsets, grouped by frame, oldest first; the last one is current
+    frames: VecDeque<Vec<(Allocation, erupt::vk::DeviceSize)>>,
+}
+
+impl<'a, S> RingAllocator<'a, S> {
+    /// Creates a ring of `size` bytes in memory type `memory_type_index`.
+    ///
+    /// The whole block is allocated up front, so this fails if the memory isn't available.
+    pub fn new(
+        allocator: &'a Allocator<S>,
+        memory_type_index: u32,
+        size: erupt::vk::DeviceSize,
+    ) -> Result<Self> {
+        let pool = allocator.create_pool(&AllocatorPoolCreateInfo {
+            memory_type_index,
+            flags: AllocatorPoolCreateFlags::LINEAR_ALGORITHM,
+            block_size: size as usize,
+            min_block_count: 1,
+            max_block_count: 1,
+            ..Default::default()
+        })?;
+        Ok(RingAllocator {
+            allocator,
+            pool,
+            memory_type_index,
+            size,
+            head: 0,
+            frames: VecDeque::from(vec![Vec::new()]),
+        })
+    }
+
+    /// Allocates `size` bytes aligned to `alignment` for the current frame.
+    ///
+    /// Returns the allocation together with its offset inside the ring's memory block. The offset
+    /// is smaller than the one of the previous allocation when the ring has wrapped around.
+    /// `alignment` must be a power of two; 0 means no special alignment.
+    pub fn alloc(
+        &mut self,
+        size: erupt::vk::DeviceSize,
+        alignment: erupt::vk::DeviceSize,
+    ) -> Result<(Allocation, erupt::vk::DeviceSize)> {
+        let memory_requirements = erupt::vk::MemoryRequirements {
+            size,
+            alignment,
+            memory_type_bits: 1 << self.memory_type_index,
+        };
+        let allocation_info = AllocationCreateInfo {
+            pool: Some(self.pool.clone()),
+            ..Default::default()
+        };
+        // The linear algorithm only wraps around into space freed by `release_frame`, and fails
+        // with ERROR_OUT_OF_DEVICE_MEMORY if that isn't enough.
+        let (allocation, allocation_info) = self
+            .allocator
+            .allocate_memory(&memory_requirements, &allocation_info)?;
+        let offset = allocation_info.get_offset();
+        self.head = offset + allocation_info.get_size();
+        self.frames
+            .back_mut()
+            .expect("the current frame is always present")
+            .push((allocation, offset));
+        Ok((allocation, offset))
+    }
+
+    /// Ends the current frame and starts a new one.
+    ///
+    /// The allocations of the ended frame stay alive until it's freed by
+    /// `RingAllocator::release_frame`.
+    pub fn end_frame(&mut self) {
+        self.frames.push_back(Vec::new());
+    }
+
+    /// Frees all allocations of the oldest frame ended with `RingAllocator::end_frame`, making
+    /// their space available to allocations that wrap around. Does nothing if no frame has been
+    /// ended since the last release.
+    ///
+    /// The caller must make sure that the GPU has finished using them, e.g. by waiting on the
+    /// fence of the frame that used them.
+    pub fn release_frame(&mut self) {
+        if self.frames.len() < 2 {
+            return;
+        }
+        if let Some(frame) = self.frames.pop_front() {
+            self.free(frame);
+        }
+        if self.frames.iter().all(Vec::is_empty) {
+            self.head = 0;
+        }
+    }
+
+    /// Frees the allocations of all frames, including the current one, making the whole ring
+    /// available again.
+    ///
+    /// The caller must make sure that the GPU has finished using all of them.
+    pub fn reset_frame(&mut self) {
+        for frame in std::mem::take(&mut self.frames) {
+            self.free(frame);
+        }
+        self.frames.push_back(Vec::new());
+        self.head = 0;
+    }
+
+    /// Returns the number of bytes between the oldest allocation still alive and the end of the
+    /// most recent one, including alignment padding and, after wrapping around, the unused space
+    /// at the end of the block.
+    pub fn used(&self) -> erupt::vk::DeviceSize {
+        match self.frames.iter().flatten().next() {
+            None => 0,
+            Some(&(_, tail)) if self.head > tail => self.head - tail,
+            Some(&(_, tail)) => self.size - tail + self.head,
+        }
+    }
+
+    /// Returns the total size of the ring in bytes.
+    pub fn size(&self) -> erupt::vk::DeviceSize {
+        self.size
+    }
+
+    /// Returns the custom pool backing this ring.
+    pub fn pool(&self) -> &AllocatorPool {
+        &self.pool
+    }
+
+    fn free(&self, frame: Vec<(Allocation, erupt::vk::DeviceSize)>) {
+        if !frame.is_empty() {
+            let allocations: Vec<Allocation> = frame
+                .into_iter()
+                .map(|(allocation, _)| allocation)
+                .collect();
+            self.allocator.free_memory_pages(&allocations);
+        }
+    }
+}
+
+impl<S> Drop for RingAllocator<'_, S> {
+    fn drop(&mut self) {
+        self.reset_frame();
+        self.allocator.destroy_pool(&self.pool);
+    }
+}
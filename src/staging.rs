@@ -0,0 +1,110 @@
+//! Uploading data to device local buffers through a temporary staging buffer.
+
+use crate::error::{Error, Result};
+use crate::{Allocation, AllocationCreateFlags, AllocationCreateInfo, Allocator, MemoryUsage};
+
+/// Buffers created by `Allocator::create_buffer_from_data`, together with the copy that still
+/// has to be recorded by the caller.
+///
+/// `staging_buffer` already contains the data. To finish the upload, record
+/// `erupt::DeviceLoader::cmd_copy_buffer(command_buffer, staging_buffer, buffer, &[region])`,
+/// submit it, and once the submission has completed, release the staging buffer with
+/// `StagingUpload::destroy_staging`. `buffer` and `allocation` are owned by the caller and
+/// should be destroyed with `Allocator::destroy_buffer`.
+#[derive(Debug, Clone, Copy)]
+pub struct StagingUpload {
+    /// Host visible buffer holding the uploaded data, usable as a transfer source.
+    pub staging_buffer: erupt::vk::Buffer,
+
+    /// Memory of `staging_buffer`.
+    pub staging_allocation: Allocation,
+
+    /// Destination buffer, preferably in device local memory.
+    pub buffer: erupt::vk::Buffer,
+
+    /// Memory of `buffer`.
+    pub allocation: Allocation,
+
+    /// Region to copy from `staging_buffer` to `buffer`, covering all of the data.
+    pub region: erupt::vk::BufferCopy,
+}
+
+impl StagingUpload {
+    /// Destroys the staging buffer and frees its memory.
+    ///
+    /// The copy out of it must have finished executing on the GPU.
+    pub fn destroy_staging<S>(&self, allocator: &Allocator<S>) {
+        allocator.destroy_buffer(self.staging_buffer, &self.staging_allocation);
+    }
+}
+
+impl<S> Allocator<S> {
+    /// Creates a buffer with `usage` for the contents of `data`, together with a host visible
+    /// staging buffer already filled with it.
+    ///
+    /// The library can't record commands, so the copy between the two buffers is returned in
+    /// `StagingUpload` for the caller to record and submit. `erupt::vk::BufferUsageFlags::TRANSFER_DST`
+    /// is added to `usage` automatically.
+    ///
+    /// The destination buffer is created with `MemoryUsage::AutoPreferDevice`. If any step fails,
+    /// everything created so far is destroyed before the error is returned.
+    ///
+    /// If `data` is empty, an error of kind `ErrorKind::Config` is returned, as Vulkan doesn't
+    /// allow buffers of size 0.
+    pub fn create_buffer_from_data(
+        &self,
+        usage: erupt::vk::BufferUsageFlags,
+        data: &[u8],
+    ) -> Result<StagingUpload> {
+        if data.is_empty() {
+            return Err(Error::config("can't create a buffer from empty data"));
+        }
+        let size = data.len() as erupt::vk::DeviceSize;
+
+        let (staging_buffer, staging_allocation, _) = self.create_buffer(
+            &erupt::vk::BufferCreateInfoBuilder::new()
+                .size(size)
+                .usage(erupt::vk::BufferUsageFlags::TRANSFER_SRC)
+                .sharing_mode(erupt::vk::SharingMode::EXCLUSIVE),
+            &AllocationCreateInfo {
+                usage: MemoryUsage::Auto,
+                flags: AllocationCreateFlags::HOST_ACCESS_SEQUENTIAL_WRITE
+                    | AllocationCreateFlags::MAPPED,
+                ..Default::default()
+            },
+        )?;
+        if let Err(err) = self.write_bytes(&staging_allocation, 0, data) {
+            self.destroy_buffer(staging_buffer, &staging_allocation);
+            return Err(err);
+        }
+
+        let (buffer, allocation, _) = match self.create_buffer(
+            &erupt::vk::BufferCreateInfoBuilder::new()
+                .size(size)
+                .usage(usage | erupt::vk::BufferUsageFlags::TRANSFER_DST)
+                .sharing_mode(erupt::vk::SharingMode::EXCLUSIVE),
+            &AllocationCreateInfo {
+                usage: MemoryUsage::AutoPreferDevice,
+                ..Default::default()
+            },
+        ) {
+            Ok(created) => created,
+            Err(err) => {
+                self.destroy_buffer(staging_buffer, &staging_allocation);
+                return Err(err);
+            }
+        };
+
+        Ok(StagingUpload {
+            staging_buffer,
+            staging_allocation,
+            buffer,
+            allocation,
+            region: erupt::vk::BufferCopy {
+                src_offset: 0,
+                dst_offset: 0,
+                size,
+            },
+        })
+    }
+}
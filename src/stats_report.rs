@@ -0,0 +1,214 @@
+//! Typed representation of the JSON statistics built by `Allocator::build_stats_string`.
+
+use crate::error::{Error, Result};
+use crate::{Allocator, StatsDetail};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Statistics of memory usage as reported in the JSON dump of the library.
+///
+/// Minimum and maximum sizes are only written by VMA when there is more than one
+/// allocation or unused range respectively, hence they are optional.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DetailedStatsReport {
+    /// Number of `erupt::vk::DeviceMemory` objects - Vulkan memory blocks allocated.
+    pub block_count: u32,
+
+    /// Number of bytes allocated in `erupt::vk::DeviceMemory` blocks.
+    pub block_bytes: u64,
+
+    /// Number of `Allocation` objects allocated.
+    pub allocation_count: u32,
+
+    /// Total number of bytes occupied by all `Allocation` objects.
+    pub allocation_bytes: u64,
+
+    /// Number of free ranges of memory between allocations.
+    pub unused_range_count: u32,
+
+    /// Smallest allocation size.
+    pub allocation_size_min: Option<u64>,
+
+    /// Largest allocation size.
+    pub allocation_size_max: Option<u64>,
+
+    /// Smallest empty range size.
+    pub unused_range_size_min: Option<u64>,
+
+    /// Largest empty range size.
+    pub unused_range_size_max: Option<u64>,
+}
+
+/// Memory budget of a heap as reported in the JSON dump of the library.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct BudgetReport {
+    /// Estimated amount of memory available to the program, in bytes.
+    pub budget_bytes: u64,
+
+    /// Estimated current memory usage of the program, in bytes.
+    pub usage_bytes: u64,
+}
+
+/// Statistics of a single memory type.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryTypeStatsReport {
+    /// Index of the memory type.
+    pub index: u32,
+
+    /// Names of `erupt::vk::MemoryPropertyFlags` of the memory type, e.g. `"DEVICE_LOCAL"`.
+    pub flags: Vec<String>,
+
+    /// Statistics of the memory type.
+    pub stats: DetailedStatsReport,
+}
+
+/// Statistics of a single memory heap and the memory types located in it.
+#[derive(Debug, Default, Clone)]
+pub struct HeapStatsReport {
+    /// Index of the memory heap.
+    pub index: u32,
+
+    /// Names of `erupt::vk::MemoryHeapFlags` of the heap, e.g. `"DEVICE_LOCAL"`.
+    pub flags: Vec<String>,
+
+    /// Size of the heap in bytes.
+    pub size: u64,
+
+    /// Budget of the heap.
+    pub budget: Option<BudgetReport>,
+
+    /// Statistics of the heap.
+    pub stats: DetailedStatsReport,
+
+    /// Statistics of the memory types located in this heap, sorted by memory type index.
+    pub memory_types: Vec<MemoryTypeStatsReport>,
+}
+
+/// Parsed statistics returned by `Allocator::build_stats`.
+#[derive(Debug, Clone)]
+pub struct StatsReport {
+    /// General information about the allocator and the physical device.
+    pub general: serde_json::Value,
+
+    /// Statistics summed over all memory heaps and types.
+    pub total: DetailedStatsReport,
+
+    /// Statistics of each memory heap, sorted by memory heap index.
+    pub heaps: Vec<HeapStatsReport>,
+
+    /// Detailed map of blocks and allocations in default pools, present only when requested.
+    pub default_pools: Option<serde_json::Value>,
+
+    /// Detailed map of blocks and allocations in custom pools, present only when requested.
+    pub custom_pools: Option<serde_json::Value>,
+}
+
+impl StatsReport {
+    /// Iterates over statistics of all memory types, in order of heaps they belong to.
+    pub fn memory_types(&self) -> impl Iterator<Item = &MemoryTypeStatsReport> {
+        self.heaps.iter().flat_map(|heap| heap.memory_types.iter())
+    }
+
+    /// Parses the JSON string produced by `Allocator::build_stats_string`.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let raw: RawStatsReport =
+            serde_json::from_str(json).map_err(|err| Error::parse(err.to_string()))?;
+        let mut heaps = raw
+            .memory_info
+            .into_iter()
+            .map(|(name, heap)| {
+                let mut memory_types = heap
+                    .memory_pools
+                    .into_iter()
+                    .map(|(name, memory_type)| {
+                        Ok(MemoryTypeStatsReport {
+                            index: parse_index(&name, "Type ")?,
+                            flags: memory_type.flags,
+                            stats: memory_type.stats,
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                memory_types.sort_by_key(|memory_type| memory_type.index);
+                Ok(HeapStatsReport {
+                    index: parse_index(&name, "Heap ")?,
+                    flags: heap.flags,
+                    size: heap.size,
+                    budget: heap.budget,
+                    stats: heap.stats,
+                    memory_types,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        heaps.sort_by_key(|heap| heap.index);
+        Ok(StatsReport {
+            general: raw.general,
+            total: raw.total,
+            heaps,
+            default_pools: raw.default_pools,
+            custom_pools: raw.custom_pools,
+        })
+    }
+}
+
+/// Extracts the index from a key like `"Heap 3"`.
+fn parse_index(name: &str, prefix: &str) -> Result<u32> {
+    name.strip_prefix(prefix)
+        .and_then(|index| index.parse().ok())
+        .ok_or_else(|| Error::parse(format!("unexpected key in stats string: {}", name)))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawStatsReport {
+    general: serde_json::Value,
+    total: DetailedStatsReport,
+    #[serde(default)]
+    memory_info: BTreeMap<String, RawHeap>,
+    default_pools: Option<serde_json::Value>,
+    custom_pools: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawHeap {
+    #[serde(default)]
+    flags: Vec<String>,
+    size: u64,
+    budget: Option<BudgetReport>,
+    stats: DetailedStatsReport,
+    #[serde(default)]
+    memory_pools: BTreeMap<String, RawMemoryType>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawMemoryType {
+    #[serde(default)]
+    flags: Vec<String>,
+    stats: DetailedStatsReport,
+}
+
+impl<S> Allocator<S> {
+    /// Builds statistics in `JSON` format and parses them into a `StatsReport`.
+    ///
+    /// Same as `Allocator::build_stats_with` with `StatsDetail::Full` if `detailed_map` is true,
+    /// and `StatsDetail::Summary` otherwise.
+    pub fn build_stats(&self, detailed_map: bool) -> Result<StatsReport> {
+        self.build_stats_with(if detailed_map {
+            StatsDetail::Full
+        } else {
+            StatsDetail::Summary
+        })
+    }
+
+    /// Builds statistics in `JSON` format, with the amount of detail given by `detail`, and parses
+    /// them into a `StatsReport`.
+    ///
+    /// With `StatsDetail::Full`, `StatsReport::default_pools` and `StatsReport::custom_pools`
+    /// contain the detailed map of blocks and allocations.
+    pub fn build_stats_with(&self, detail: StatsDetail) -> Result<StatsReport> {
+        StatsReport::from_json(&self.build_stats_string_with(detail)?)
+    }
+}
@@ -0,0 +1,271 @@
+//! Virtual allocator - allocation algorithm of the library without any Vulkan memory behind it.
+
+use crate::error::{Error, Result};
+use crate::ffi;
+
+bitflags! {
+    /// Flags for configuring `VirtualBlock` construction.
+    pub struct VirtualBlockCreateFlags: u32 {
+        /// Default configuration.
+        const NONE = 0;
+
+        /// Enables alternative, linear allocation algorithm in this virtual block.
+        ///
+        /// Specify this flag to enable linear allocation algorithm, which always creates
+        /// new allocations after last one and doesn't reuse space from allocations freed in
+        /// between. It trades memory consumption for simplified algorithm and data
+        /// structure, which has better performance and uses less memory for metadata.
+        ///
+        /// By using this flag, you can achieve behavior of free-at-once, stack,
+        /// ring buffer, and double stack.
+        const LINEAR_ALGORITHM = ffi::VmaVirtualBlockCreateFlagBits_VMA_VIRTUAL_BLOCK_CREATE_LINEAR_ALGORITHM_BIT;
+
+        /// Bit mask to extract only `ALGORITHM` bits from entire set of flags.
+        const ALGORITHM_MASK = ffi::VmaVirtualBlockCreateFlagBits_VMA_VIRTUAL_BLOCK_CREATE_ALGORITHM_MASK;
+    }
+}
+
+impl Default for VirtualBlockCreateFlags {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+bitflags! {
+    /// Flags for configuring `VirtualAllocation` construction.
+    pub struct VirtualAllocationCreateFlags: u32 {
+        /// Default configuration for allocation.
+        const NONE = 0;
+
+        /// Allocation will be created from upper stack in a double stack pool.
+        ///
+        /// This flag is only allowed for virtual blocks created with `VirtualBlockCreateFlags::LINEAR_ALGORITHM` flag.
+        const UPPER_ADDRESS = ffi::VmaVirtualAllocationCreateFlagBits_VMA_VIRTUAL_ALLOCATION_CREATE_UPPER_ADDRESS_BIT;
+
+        /// Allocation strategy that tries to minimize memory usage.
+        const STRATEGY_MIN_MEMORY = ffi::VmaVirtualAllocationCreateFlagBits_VMA_VIRTUAL_ALLOCATION_CREATE_STRATEGY_MIN_MEMORY_BIT;
+
+        /// Allocation strategy that tries to minimize allocation time.
+        const STRATEGY_MIN_TIME = ffi::VmaVirtualAllocationCreateFlagBits_VMA_VIRTUAL_ALLOCATION_CREATE_STRATEGY_MIN_TIME_BIT;
+
+        /// Allocation strategy that chooses always the lowest offset in available space.
+        /// This is not the most efficient strategy but achieves highly packed data.
+        const STRATEGY_MIN_OFFSET = ffi::VmaVirtualAllocationCreateFlagBits_VMA_VIRTUAL_ALLOCATION_CREATE_STRATEGY_MIN_OFFSET_BIT;
+
+        /// A bit mask to extract only `STRATEGY` bits from entire set of flags.
+        const STRATEGY_MASK = ffi::VmaVirtualAllocationCreateFlagBits_VMA_VIRTUAL_ALLOCATION_CREATE_STRATEGY_MASK;
+    }
+}
+
+impl Default for VirtualAllocationCreateFlags {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+/// Description of a `VirtualBlock` to be created.
+#[derive(Default, Debug, Clone)]
+pub struct VirtualBlockCreateInfo {
+    /// Total size of the virtual block.
+    ///
+    /// Sizes can be expressed in bytes or any units you want as long as you are consistent in using them.
+    /// For example, if you allocate from some array of structures, 1 can mean single instance of entire structure.
+    pub size: erupt::vk::DeviceSize,
+
+    /// Flags for configuring the virtual block.
+    pub flags: VirtualBlockCreateFlags,
+}
+
+/// Description of a `VirtualAllocation` to be created.
+#[derive(Debug, Clone)]
+pub struct VirtualAllocationCreateInfo {
+    /// Size of the allocation.
+    ///
+    /// Cannot be zero.
+    pub size: erupt::vk::DeviceSize,
+
+    /// Required alignment of the allocation. Optional.
+    ///
+    /// Must be power of two. Special value 0 has the same meaning as 1 - means no special alignment is required, so allocation can start at any offset.
+    pub alignment: erupt::vk::DeviceSize,
+
+    /// Flags for configuring the allocation.
+    pub flags: VirtualAllocationCreateFlags,
+
+    /// Custom pointer to be associated with the allocation.
+    pub user_data: *mut ::std::os::raw::c_void,
+}
+
+impl Default for VirtualAllocationCreateInfo {
+    fn default() -> Self {
+        VirtualAllocationCreateInfo {
+            size: 0,
+            alignment: 0,
+            flags: VirtualAllocationCreateFlags::NONE,
+            user_data: ::std::ptr::null_mut(),
+        }
+    }
+}
+
+/// Represents single memory allocation done inside `VirtualBlock`.
+#[derive(Debug, Copy, Clone)]
+pub struct VirtualAllocation {
+    /// Pointer to internal VmaVirtualAllocation instance
+    internal: ffi::VmaVirtualAllocation,
+}
+
+unsafe impl Send for VirtualAllocation {}
+unsafe impl Sync for VirtualAllocation {}
+
+/// Parameters of created `VirtualAllocation`, returned by `VirtualBlock::get_allocation_info`.
+#[derive(Debug, Clone)]
+pub struct VirtualAllocationInfo {
+    /// Offset of the allocation.
+    ///
+    /// Offset at which the allocation was made.
+    pub offset: erupt::vk::DeviceSize,
+
+    /// Size of the allocation.
+    ///
+    /// Same value as passed in `VirtualAllocationCreateInfo::size`.
+    pub size: erupt::vk::DeviceSize,
+
+    /// Custom pointer associated with the allocation.
+    ///
+    /// Same value as passed in `VirtualAllocationCreateInfo::user_data` or to `VirtualBlock::set_virtual_allocation_user_data`.
+    pub user_data: *mut ::std::os::raw::c_void,
+}
+
+/// Handle to a virtual block object that allows to use core allocation algorithm of the library
+/// for custom purposes, without any Vulkan memory behind it.
+///
+/// Fill in `VirtualBlockCreateInfo` and call `VirtualBlock::new` to create it.
+/// The block is destroyed when dropped.
+///
+/// This object is not thread-safe - it should not be used from multiple threads simultaneously,
+/// so it is `Send` but not `Sync`.
+#[derive(Debug)]
+pub struct VirtualBlock {
+    /// Pointer to internal VmaVirtualBlock instance
+    internal: ffi::VmaVirtualBlock,
+}
+
+unsafe impl Send for VirtualBlock {}
+
+impl VirtualBlock {
+    /// Creates new `VirtualBlock` object.
+    pub fn new(create_info: &VirtualBlockCreateInfo) -> Result<Self> {
+        let ffi_create_info = ffi::VmaVirtualBlockCreateInfo {
+            size: create_info.size,
+            flags: create_info.flags.bits(),
+            pAllocationCallbacks: ::std::ptr::null(),
+        };
+        let mut internal: ffi::VmaVirtualBlock = ::std::ptr::null_mut();
+        let result = crate::ffi_to_result(unsafe {
+            ffi::vmaCreateVirtualBlock(&ffi_create_info, &mut internal)
+        });
+        match result {
+            erupt::vk::Result::SUCCESS => Ok(VirtualBlock { internal }),
+            _ => Err(Error::vulkan(result)),
+        }
+    }
+
+    /// Allocates new virtual allocation inside this virtual block.
+    ///
+    /// Returns the allocation together with its offset inside the block.
+    ///
+    /// If the allocation fails due to not enough free space available,
+    /// `erupt::vk::Result::ERROR_OUT_OF_DEVICE_MEMORY` is returned (despite the function doesn't ever allocate actual GPU memory).
+    pub fn allocate(
+        &self,
+        info: &VirtualAllocationCreateInfo,
+    ) -> Result<(VirtualAllocation, erupt::vk::DeviceSize)> {
+        let ffi_info = ffi::VmaVirtualAllocationCreateInfo {
+            size: info.size,
+            alignment: info.alignment,
+            flags: info.flags.bits(),
+            pUserData: info.user_data,
+        };
+        let mut allocation = VirtualAllocation {
+            internal: ::std::ptr::null_mut(),
+        };
+        let mut offset: erupt::vk::DeviceSize = 0;
+        let result = crate::ffi_to_result(unsafe {
+            ffi::vmaVirtualAllocate(
+                self.internal,
+                &ffi_info,
+                &mut allocation.internal,
+                &mut offset,
+            )
+        });
+        match result {
+            erupt::vk::Result::SUCCESS => Ok((allocation, offset)),
+            _ => Err(Error::vulkan(result)),
+        }
+    }
+
+    /// Frees virtual allocation inside this virtual block.
+    ///
+    /// The allocation must have been returned by `VirtualBlock::allocate` on this block.
+    pub fn free(&self, allocation: VirtualAllocation) {
+        unsafe {
+            ffi::vmaVirtualFree(self.internal, allocation.internal);
+        }
+    }
+
+    /// Returns information about a specific virtual allocation within this virtual block, like its size and user data.
+    pub fn get_allocation_info(&self, allocation: &VirtualAllocation) -> VirtualAllocationInfo {
+        let mut ffi_info: ffi::VmaVirtualAllocationInfo = Default::default();
+        unsafe {
+            ffi::vmaGetVirtualAllocationInfo(self.internal, allocation.internal, &mut ffi_info);
+        }
+        VirtualAllocationInfo {
+            offset: ffi_info.offset,
+            size: ffi_info.size,
+            user_data: ffi_info.pUserData,
+        }
+    }
+
+    /// Changes custom pointer associated with given virtual allocation.
+    ///
+    /// The pointer is never dereferenced by the library, so it can be used to store any pointer-sized value.
+    pub fn set_virtual_allocation_user_data(
+        &self,
+        allocation: &VirtualAllocation,
+        user_data: *mut ::std::os::raw::c_void,
+    ) {
+        unsafe {
+            ffi::vmaSetVirtualAllocationUserData(self.internal, allocation.internal, user_data);
+        }
+    }
+
+    /// Returns true if this virtual block is empty - contains 0 virtual allocations and has all its space available for new allocations.
+    pub fn is_empty(&self) -> bool {
+        unsafe { ffi::vmaIsVirtualBlockEmpty(self.internal) == erupt::vk::TRUE }
+    }
+
+    /// Frees all virtual allocations inside this virtual block.
+    ///
+    /// You must either call this function or free each virtual allocation individually with `VirtualBlock::free`
+    /// before destroying a virtual block. Otherwise, an assert is called.
+    ///
+    /// If you keep pointers to some additional metadata associated with your virtual allocations in their `user_data`,
+    /// don't forget to free them.
+    pub fn clear(&self) {
+        unsafe {
+            ffi::vmaClearVirtualBlock(self.internal);
+        }
+    }
+}
+
+/// Custom `Drop` implementation to clean up internal virtual block instance
+impl Drop for VirtualBlock {
+    fn drop(&mut self) {
+        if !self.internal.is_null() {
+            unsafe {
+                ffi::vmaDestroyVirtualBlock(self.internal);
+            }
+            self.internal = ::std::ptr::null_mut();
+        }
+    }
+}
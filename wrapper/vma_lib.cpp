@@ -1,2 +1,25 @@
+#ifdef VMA_RUST_NONFATAL_CORRUPTION_ASSERT
+#ifndef NDEBUG
+#include <cstdio>
+#include <cstdlib>
+#include <cstring>
+
+// Corruption found by vmaCheckCorruption/vmaCheckPoolCorruption is reported
+// to Rust through the returned VkResult, so only log those assertions instead
+// of aborting the process. Any other failed assertion still aborts.
+static void vmaRustAssertFailed(const char* expr, const char* file, int line)
+{
+    fprintf(stderr, "VMA_ASSERT failed: %s (%s:%d)\n", expr, file, line);
+    if (strstr(expr, "CORRUPTION") == nullptr)
+    {
+        abort();
+    }
+}
+
+#define VMA_ASSERT(expr) \
+    do { if (!(expr)) { vmaRustAssertFailed(#expr, __FILE__, __LINE__); } } while (false)
+#endif
+#endif
+
 #define VMA_IMPLEMENTATION
 #include "vk_mem_alloc.h"